@@ -29,14 +29,19 @@ async fn main() -> Result<()> {
     let app = Router::new()
         .get("/", index)
         .post("/", create)
-        .with(csrf::Config::new(
-            csrf::Store::Cookie,
-            [Method::GET, Method::HEAD, Method::OPTIONS, Method::TRACE].into(),
-            CookieOptions::new("_csrf").max_age(Duration::from_secs(3600 * 24)),
-            csrf::secret,
-            csrf::generate,
-            csrf::verify,
-        ))
+        .with(
+            csrf::Config::new(
+                csrf::Store::Cookie,
+                [Method::GET, Method::HEAD, Method::OPTIONS, Method::TRACE].into(),
+                CookieOptions::new("_csrf").max_age(Duration::from_secs(3600 * 24)),
+                csrf::secret,
+                csrf::generate,
+                csrf::verify,
+            )
+            // Also accepts the token from the `_csrf` field of HTML forms.
+            .form_field("_csrf")
+            .same_origin(true),
+        )
         .with(cookie::Config::default());
 
     if let Err(e) = serve(listener, app).await {
@@ -1,45 +1,42 @@
 #![deny(warnings)]
 
-use futures_util::TryStreamExt;
-use std::{fs::File, net::SocketAddr};
+use serde::Deserialize;
+use std::net::SocketAddr;
 use tempfile::tempdir;
 use tokio::net::TcpListener;
 use viz::{
     middleware::limits,
     serve,
-    types::{Multipart, PayloadError},
-    IntoHandler, IntoResponse, Request, Response, ResponseExt, Result, Router,
+    types::{MultipartForm, MultipartFormConfig, State},
+    HandlerExt, IntoHandler, Request, Response, ResponseExt, Result, Router,
 };
 
+#[derive(Debug, Deserialize)]
+struct Upload {
+    group: Option<String>,
+}
+
 // HTML form for uploading photos
 async fn new(_: Request) -> Result<Response> {
     Ok(Response::html(include_str!("../index.html")))
 }
 
 // upload photos
-async fn upload(mut form: Multipart) -> Result<Response> {
+async fn upload(MultipartForm(upload, mut files): MultipartForm<Upload>) -> Result<String> {
     let dir = tempdir()?;
 
-    let mut group = None;
-
-    while let Some(mut field) = form.try_next().await? {
-        if let Some(ref filename) = field.filename {
-            let path = dir.path().join(filename);
-            let mut file = File::create(&path)?;
-            field.copy_to_file(&mut file).await?;
-        } else {
-            let buf = field.bytes().await?;
-            group.replace(String::from_utf8(buf.to_vec()).map_err(PayloadError::Utf8)?);
-        }
+    for (i, photo) in files.take_all("photos").into_iter().enumerate() {
+        // only the final component of the client's file name is kept
+        let filename = photo
+            .file_name()
+            .map_or_else(|| format!("photo-{i}"), ToString::to_string);
+        photo.persist(dir.path().join(filename)).await?;
     }
 
     // clean the dir
     dir.close()?;
 
-    Ok(match group {
-        Some(group) => group.into_response(),
-        None => "Default".into_response(),
-    })
+    Ok(upload.group.unwrap_or_else(|| "Default".to_string()))
 }
 
 #[tokio::main]
@@ -50,7 +47,15 @@ async fn main() -> Result<()> {
 
     let app = Router::new()
         .get("/", new)
-        .post("/", upload.into_handler())
+        .post(
+            "/",
+            upload.into_handler().with(State::new(
+                // only accepts the images up to 1MB
+                MultipartFormConfig::new()
+                    .max_file_size(1024 * 1024)
+                    .allow(mime::IMAGE_STAR),
+            )),
+        )
         // limit body size
         .with(limits::Config::default());
 
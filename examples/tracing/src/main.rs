@@ -4,7 +4,7 @@ use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tracing::{debug, error, info, instrument};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use viz::{serve, Request, RequestExt, Result, Router};
+use viz::{middleware::logger, serve, Request, RequestExt, Result, Router};
 
 #[instrument]
 async fn index(req: Request) -> Result<&'static str> {
@@ -17,7 +17,7 @@ async fn main() -> Result<()> {
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "tracing=debug,hyper=debug".into()),
+                .unwrap_or_else(|_| "tracing=debug,hyper=debug,viz::logger=info".into()),
         )
         .with(tracing_subscriber::fmt::layer())
         .init();
@@ -26,7 +26,10 @@ async fn main() -> Result<()> {
     let listener = TcpListener::bind(addr).await?;
     info!("listening on http://{addr}");
 
-    let app = Router::new().get("/", index);
+    let app = Router::new()
+        .get("/", index)
+        // Access log in the Combined Log Format
+        .with(logger::Config::new().format(logger::Format::Combined));
 
     if let Err(e) = serve(listener, app).await {
         error!("{e}");
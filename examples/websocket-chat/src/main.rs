@@ -18,7 +18,7 @@ async fn index() -> Result<Response> {
 }
 
 async fn ws(mut req: Request) -> Result<impl IntoResponse> {
-    let (ws, Params(name), State(sender)): (WebSocket, Params<String>, State<Sender<String>>) =
+    let (Params(name), State(sender), ws): (Params<String>, State<Sender<String>>, WebSocket) =
         req.extract().await?;
 
     let tx = sender.clone();
@@ -28,6 +28,17 @@ impl Error {
         Self::Boxed(t.into())
     }
 
+    /// Converts a boxed error to a report with an empty `500` response, so the middleware can
+    /// change the response and keep the error, the message of the error is not exposed.
+    #[cfg(feature = "request-id")]
+    #[must_use]
+    pub(crate) fn into_report(self) -> Self {
+        match self {
+            Self::Boxed(e) => Self::Report(e, StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+            err => err,
+        }
+    }
+
     /// Forwards to the method defined on the type `dyn Error`.
     #[inline]
     pub fn is<T>(&self) -> bool
@@ -1,8 +1,11 @@
 //! Extracts data from the [`Request`] by types.
 
-use crate::{Future, IntoResponse, Request};
+use crate::{types::PayloadError, Body, Future, IntoResponse, Request, RequestExt};
 
 /// An interface for extracting data from the HTTP [`Request`].
+///
+/// The extractor may consume the body, so only the last argument of a handler can be a
+/// `FromRequest` extractor, the others must be [`FromRequestParts`] extractors.
 pub trait FromRequest: Sized {
     /// The type returned in the event of a conversion error.
     type Error: IntoResponse;
@@ -12,6 +15,20 @@ pub trait FromRequest: Sized {
     fn extract(req: &mut Request) -> impl Future<Output = Result<Self, Self::Error>> + Send;
 }
 
+/// An interface for extracting data from the parts of the HTTP [`Request`], e.g. the headers,
+/// the params, the state or the cookies, without consuming the body.
+///
+/// The parts extractors of a handler are extracted concurrently. A parts extractor should
+/// implement the [`FromRequest`] too, so it can be the last argument of a handler.
+pub trait FromRequestParts: Sized {
+    /// The type returned in the event of a conversion error.
+    type Error: IntoResponse;
+
+    /// Extracts this type from the parts of the HTTP [`Request`].
+    #[must_use]
+    fn extract_parts(req: &Request) -> impl Future<Output = Result<Self, Self::Error>> + Send;
+}
+
 impl<T> FromRequest for Option<T>
 where
     T: FromRequest,
@@ -23,6 +40,17 @@ where
     }
 }
 
+impl<T> FromRequestParts for Option<T>
+where
+    T: FromRequestParts,
+{
+    type Error = std::convert::Infallible;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        Ok(T::extract_parts(req).await.ok())
+    }
+}
+
 impl<T> FromRequest for Result<T, T::Error>
 where
     T: FromRequest,
@@ -33,3 +61,23 @@ where
         Ok(T::extract(req).await)
     }
 }
+
+impl<T> FromRequestParts for Result<T, T::Error>
+where
+    T: FromRequestParts,
+{
+    type Error = std::convert::Infallible;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        Ok(T::extract_parts(req).await)
+    }
+}
+
+/// Extracts the incoming body of the request, e.g. for streaming the body.
+impl FromRequest for Body {
+    type Error = PayloadError;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        req.incoming()
+    }
+}
@@ -26,6 +26,9 @@ pub use catch_unwind::CatchUnwind;
 mod either;
 pub use either::Either;
 
+mod endpoint;
+pub use endpoint::Endpoint;
+
 mod fn_ext;
 pub use fn_ext::FnExt;
 
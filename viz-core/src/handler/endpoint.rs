@@ -0,0 +1,13 @@
+use crate::{Handler, Method, Request, Response, Result};
+
+/// A handler which declares the path and the HTTP method of its route.
+///
+/// It is generated by the route macros, e.g. `#[get("/users/:id")]`, and the endpoints can be
+/// mounted on the router in one call, e.g. `Router::new().mount(routes![show_user])`.
+pub trait Endpoint: Handler<Request, Output = Result<Response>> + Clone {
+    /// The pattern of the path, e.g. `/users/:id`.
+    const PATH: &'static str;
+
+    /// The HTTP method of the route.
+    const METHOD: Method;
+}
@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use crate::{FnExt, FromRequest, Handler, IntoResponse, Result};
+use crate::{FnExt, Handler, IntoResponse, Result};
 
 /// A wrapper of the extractors handler.
 #[derive(Debug)]
@@ -26,8 +26,7 @@ impl<H, E, O> FnExtHandler<H, E, O> {
 impl<I, H, E, O> Handler<I> for FnExtHandler<H, E, O>
 where
     I: Send + 'static,
-    E: FromRequest + 'static,
-    E::Error: IntoResponse,
+    E: 'static,
     H: FnExt<I, E, Output = Result<O>>,
     O: 'static,
 {
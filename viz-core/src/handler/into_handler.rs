@@ -1,4 +1,4 @@
-use crate::{handler::FnExtHandler, FnExt, FromRequest, Handler, IntoResponse, Result};
+use crate::{handler::FnExtHandler, FnExt, Handler, Result};
 
 /// The trait implemented by types that can be converted to a [`Handler`].
 pub trait IntoHandler<I, E> {
@@ -13,8 +13,7 @@ pub trait IntoHandler<I, E> {
 impl<I, H, E, O> IntoHandler<I, E> for H
 where
     I: Send + 'static,
-    E: FromRequest + 'static,
-    E::Error: IntoResponse,
+    E: 'static,
     H: FnExt<I, E, Output = Result<O>>,
     O: 'static,
 {
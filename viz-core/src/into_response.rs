@@ -15,6 +15,15 @@ pub trait IntoResponse: Sized {
     fn into_error(self) -> Error {
         Error::Responder(self.into_response())
     }
+
+    /// Convert self to the output of a handler, the errors are kept as [`Error`]s.
+    ///
+    /// # Errors
+    ///
+    /// Will return the [`Error`] if self is an error.
+    fn into_result(self) -> Result<Response> {
+        Ok(self.into_response())
+    }
 }
 
 impl IntoResponse for Response {
@@ -37,6 +46,10 @@ impl IntoResponse for Error {
             Error::Responder(resp) | Error::Report(_, resp) => resp,
         }
     }
+
+    fn into_error(self) -> Error {
+        self
+    }
 }
 
 impl IntoResponse for std::io::Error {
@@ -127,6 +140,11 @@ where
             Err(e) => e.into_response(),
         }
     }
+
+    fn into_result(self) -> Result<Response> {
+        self.map(IntoResponse::into_response)
+            .map_err(IntoResponse::into_error)
+    }
 }
 
 impl IntoResponse for () {
@@ -15,9 +15,13 @@ pub(crate) mod macros;
 
 pub mod handler;
 #[doc(inline)]
-pub use crate::handler::{BoxHandler, FnExt, Handler, HandlerExt, IntoHandler, Next, Transform};
+pub use crate::handler::{
+    BoxHandler, Endpoint, FnExt, Handler, HandlerExt, IntoHandler, Next, Transform,
+};
 
 pub mod middleware;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod types;
 
 mod body;
@@ -27,7 +31,7 @@ mod error;
 pub use error::{BoxError, Error};
 
 mod from_request;
-pub use from_request::FromRequest;
+pub use from_request::{FromRequest, FromRequestParts};
 
 mod into_response;
 pub use into_response::IntoResponse;
@@ -60,7 +64,21 @@ pub use thiserror::Error as ThisError;
 
 #[doc(hidden)]
 mod tuples {
-    use super::{Error, FnExt, FromRequest, Future, IntoResponse, Request, Result};
+    #[cfg(feature = "openapi")]
+    use super::openapi::{Operation, OperationHandler, OperationInput, OperationOutput};
+    #[cfg(feature = "inject")]
+    use super::types::Factory;
+    use super::{
+        headers::{self, HeaderMapExt},
+        types::{Header, HeaderError, Headers, TypedHeaders},
+        Error, FnExt, FromRequest, FromRequestParts, Future, IntoResponse, Request, Response,
+        Result,
+    };
+    #[cfg(feature = "state")]
+    use super::{
+        types::{FromRef, State, StateCell, StateError, StateHandler},
+        BoxHandler, HandlerExt, IntoHandler,
+    };
 
     tuple_impls!(A B C D E F G H I J K L);
 }
@@ -1,39 +1,296 @@
 macro_rules! tuple_impls {
     () => {
         tuple_impls!(@impl);
+
+        impl FromRequestParts for () {
+            type Error = Error;
+
+            async fn extract_parts(_: &Request) -> Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+
+        impl FromRequest for () {
+            type Error = Error;
+
+            async fn extract(_: &mut Request) -> Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "inject")]
+        impl<Fun, Fut, O> Factory<()> for Fun
+        where
+            Fun: Fn() -> Fut + Send + Sync + 'static,
+            Fut: Future<Output = Result<O>> + Send,
+        {
+            type Output = O;
+
+            async fn provide(&self, _: &Request) -> Result<O> {
+                (self)().await
+            }
+        }
+
+        impl<Fun, Fut> FnExt<Request, ()> for Fun
+        where
+            Fun: Fn() -> Fut + Send + Sync + 'static,
+            Fut: Future + Send,
+            Fut::Output: IntoResponse,
+        {
+            type Output = Result<Response>;
+
+            async fn call(&self, _: Request) -> Self::Output {
+                (self)().await.into_result()
+            }
+        }
+
+        #[cfg(feature = "openapi")]
+        impl<Fun, Fut> OperationHandler<()> for Fun
+        where
+            Fun: Fn() -> Fut,
+            Fut: Future,
+            Fut::Output: OperationOutput,
+        {
+            fn operation() -> Operation {
+                Operation::new().output::<Fut::Output>()
+            }
+        }
+
+        /// The sub-state is got from the application state in the cell.
+        #[cfg(feature = "state")]
+        impl<S, Sub, Fun, Fut> StateHandler<S, (State<Sub>,)> for Fun
+        where
+            S: Send + Sync + 'static,
+            Sub: FromRef<S> + Send + 'static,
+            Fun: Fn(State<Sub>) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future + Send,
+            Fut::Output: IntoResponse,
+        {
+            fn into_state_handler(self, cell: StateCell<S>) -> BoxHandler {
+                (move || {
+                    let fut = cell.get().map(|state| (self)(State(Sub::from_ref(state))));
+                    async move {
+                        match fut {
+                            Some(fut) => fut.await.into_result(),
+                            None => Err(StateError::new::<S>().into_error()),
+                        }
+                    }
+                })
+                .into_handler()
+                .boxed()
+            }
+        }
     };
     ($T:ident $( $U:ident )*) => {
         tuple_impls!($( $U )*);
+        tuple_impls!(@parts $T $( $U )*);
         tuple_impls!(@impl $T $( $U )*);
     };
     // "Private" internal implementation
-    (@impl $( $T:ident )*) => {
-        impl<$($T,)*> FromRequest for ($($T,)*)
+    (@parts $( $T:ident )*) => {
+        /// The parts extractors are extracted concurrently.
+        impl<$($T,)*> FromRequestParts for ($($T,)*)
         where
-            $($T: FromRequest + Send,)*
+            $($T: FromRequestParts + Send,)*
             $($T::Error: IntoResponse + Send,)*
         {
             type Error = Error;
 
-            #[allow(unused, unused_mut)]
-            async fn extract(req: &mut Request) -> Result<($($T,)*), Self::Error> {
-                Ok(($($T::extract(req).await.map_err(IntoResponse::into_error)?,)*))
+            async fn extract_parts(req: &Request) -> Result<($($T,)*), Self::Error> {
+                futures_util::try_join!($(async {
+                    $T::extract_parts(req).await.map_err(IntoResponse::into_error)
+                },)*)
+            }
+        }
+
+        impl<$($T,)*> FromRequestParts for Headers<($($T,)*)>
+        where
+            $($T: headers::Header + Send,)*
+        {
+            type Error = HeaderError;
+
+            #[allow(non_snake_case)]
+            async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+                $(let Header($T) = Header::<$T>::extract_parts(req).await?;)*
+                Ok(Self(($($T,)*)))
+            }
+        }
+
+        impl<$($T,)*> FromRequest for Headers<($($T,)*)>
+        where
+            $($T: headers::Header + Send,)*
+        {
+            type Error = HeaderError;
+
+            async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+                Self::extract_parts(req).await
             }
         }
 
-        impl<$($T,)* Fun, Fut, Out> FnExt<Request, ($($T,)*)> for Fun
+        impl<$($T,)* R> IntoResponse for TypedHeaders<($($T,)*), R>
         where
-            $($T: FromRequest + Send,)*
+            $($T: headers::Header,)*
+            R: IntoResponse,
+        {
+            #[allow(non_snake_case)]
+            fn into_response(self) -> Response {
+                let TypedHeaders(($($T,)*), t) = self;
+                let mut res = t.into_response();
+                let headers = res.headers_mut();
+                $(headers.typed_insert($T);)*
+                res
+            }
+        }
+
+        /// The factory takes the parts extractors, which are extracted concurrently.
+        #[cfg(feature = "inject")]
+        impl<$($T,)* Fun, Fut, O> Factory<($($T,)*)> for Fun
+        where
+            $($T: FromRequestParts + Send,)*
             $($T::Error: IntoResponse + Send,)*
             Fun: Fn($($T,)*) -> Fut + Send + Sync + 'static,
-            Fut: Future<Output = Result<Out>> + Send,
+            Fut: Future<Output = Result<O>> + Send,
         {
-            type Output =  Fut::Output;
+            type Output = O;
 
-            #[allow(unused, unused_mut)]
+            #[allow(non_snake_case)]
+            async fn provide(&self, req: &Request) -> Result<O> {
+                let ($($T,)*) = <($($T,)*)>::extract_parts(req).await?;
+                (self)($($T,)*).await
+            }
+        }
+    };
+    (@impl $( $T:ident )*) => {
+        /// Only the last extractor can consume the body.
+        impl<$($T,)* Last> FromRequest for ($($T,)* Last,)
+        where
+            $($T: FromRequestParts + Send,)*
+            $($T::Error: IntoResponse + Send,)*
+            Last: FromRequest + Send,
+            Last::Error: IntoResponse + Send,
+        {
+            type Error = Error;
+
+            #[allow(non_snake_case)]
+            async fn extract(req: &mut Request) -> Result<($($T,)* Last,), Self::Error> {
+                let ($($T,)*) = <($($T,)*)>::extract_parts(req).await?;
+                let last = Last::extract(req).await.map_err(IntoResponse::into_error)?;
+                Ok(($($T,)* last,))
+            }
+        }
+
+        impl<$($T,)* Last, Fun, Fut> FnExt<Request, ($($T,)* Last,)> for Fun
+        where
+            $($T: FromRequestParts + Send,)*
+            $($T::Error: IntoResponse + Send,)*
+            Last: FromRequest + Send,
+            Last::Error: IntoResponse + Send,
+            Fun: Fn($($T,)* Last) -> Fut + Send + Sync + 'static,
+            Fut: Future + Send,
+            Fut::Output: IntoResponse,
+        {
+            type Output = Result<Response>;
+
+            #[allow(non_snake_case)]
             async fn call(&self, mut req: Request) -> Self::Output {
-                (self)($($T::extract(&mut req).await.map_err(IntoResponse::into_error)?,)*)
-                    .await
+                let ($($T,)* last,) = <($($T,)* Last,)>::extract(&mut req).await?;
+                (self)($($T,)* last).await.into_result()
+            }
+        }
+
+        /// The operation is described by the extractors in order and the output.
+        #[cfg(feature = "openapi")]
+        impl<$($T,)* Last, Fun, Fut> OperationHandler<($($T,)* Last,)> for Fun
+        where
+            $($T: OperationInput,)*
+            Last: OperationInput,
+            Fun: Fn($($T,)* Last) -> Fut,
+            Fut: Future,
+            Fut::Output: OperationOutput,
+        {
+            fn operation() -> Operation {
+                Operation::new()
+                    $(.input::<$T>())*
+                    .input::<Last>()
+                    .output::<Fut::Output>()
+            }
+        }
+
+        /// The remaining [`Request`] is passed as the last argument after the extractors.
+        impl<$($T,)* Fun, Fut> FnExt<Request, ($($T,)* Request,)> for Fun
+        where
+            $($T: FromRequestParts + Send,)*
+            $($T::Error: IntoResponse + Send,)*
+            Fun: Fn($($T,)* Request) -> Fut + Send + Sync + 'static,
+            Fut: Future + Send,
+            Fut::Output: IntoResponse,
+        {
+            type Output = Result<Response>;
+
+            #[allow(non_snake_case)]
+            async fn call(&self, req: Request) -> Self::Output {
+                let ($($T,)*) = <($($T,)*)>::extract_parts(&req).await?;
+                (self)($($T,)* req).await.into_result()
+            }
+        }
+
+        #[cfg(feature = "state")]
+        impl<S, Sub, $($T,)* Last, Fun, Fut> StateHandler<S, (State<Sub>, $($T,)* Last,)> for Fun
+        where
+            S: Send + Sync + 'static,
+            Sub: FromRef<S> + Send + 'static,
+            $($T: FromRequestParts + Send + 'static,)*
+            $($T::Error: IntoResponse + Send,)*
+            Last: FromRequest + Send + 'static,
+            Last::Error: IntoResponse + Send,
+            Fun: Fn(State<Sub>, $($T,)* Last) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future + Send,
+            Fut::Output: IntoResponse,
+        {
+            #[allow(non_snake_case)]
+            fn into_state_handler(self, cell: StateCell<S>) -> BoxHandler {
+                (move |$($T: $T,)* last: Last| {
+                    let fut = cell
+                        .get()
+                        .map(|state| (self)(State(Sub::from_ref(state)), $($T,)* last));
+                    async move {
+                        match fut {
+                            Some(fut) => fut.await.into_result(),
+                            None => Err(StateError::new::<S>().into_error()),
+                        }
+                    }
+                })
+                .into_handler()
+                .boxed()
+            }
+        }
+
+        #[cfg(feature = "state")]
+        impl<S, Sub, $($T,)* Fun, Fut> StateHandler<S, (State<Sub>, $($T,)* Request,)> for Fun
+        where
+            S: Send + Sync + 'static,
+            Sub: FromRef<S> + Send + 'static,
+            $($T: FromRequestParts + Send + 'static,)*
+            $($T::Error: IntoResponse + Send,)*
+            Fun: Fn(State<Sub>, $($T,)* Request) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future + Send,
+            Fut::Output: IntoResponse,
+        {
+            #[allow(non_snake_case)]
+            fn into_state_handler(self, cell: StateCell<S>) -> BoxHandler {
+                (move |$($T: $T,)* req: Request| {
+                    let fut = cell
+                        .get()
+                        .map(|state| (self)(State(Sub::from_ref(state)), $($T,)* req));
+                    async move {
+                        match fut {
+                            Some(fut) => fut.await.into_result(),
+                            None => Err(StateError::new::<S>().into_error()),
+                        }
+                    }
+                })
+                .into_handler()
+                .boxed()
             }
         }
     };
@@ -6,8 +6,16 @@ pub mod cookie;
 pub mod cors;
 #[cfg(feature = "csrf")]
 pub mod csrf;
+#[cfg(feature = "ip-filter")]
+pub mod ip_filter;
 #[cfg(feature = "limits")]
 pub mod limits;
+#[cfg(feature = "logger")]
+pub mod logger;
+#[cfg(feature = "problem")]
+pub mod problem;
+#[cfg(feature = "request-id")]
+pub mod request_id;
 #[cfg(feature = "session")]
 pub mod session;
 
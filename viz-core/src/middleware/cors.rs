@@ -1,4 +1,11 @@
 //! CORS Middleware.
+//!
+//! Preflight requests are answered by the middleware. When a route has no `OPTIONS` handler,
+//! [`Route::cors`][route] and [`Router::cors`][router] add one which is only wrapped by the
+//! middleware, so a route can have its own CORS policy.
+//!
+//! [route]: https://docs.rs/viz/latest/viz/struct.Route.html#method.cors
+//! [router]: https://docs.rs/viz/latest/viz/struct.Router.html#method.cors
 
 use std::{collections::HashSet, fmt, sync::Arc};
 
@@ -15,13 +22,87 @@ use crate::{
     Handler, IntoResponse, Method, Request, RequestExt, Response, Result, StatusCode, Transform,
 };
 
+/// The `Access-Control-Request-Private-Network` header.
+const ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK: HeaderName =
+    HeaderName::from_static("access-control-request-private-network");
+
+/// The `Access-Control-Allow-Private-Network` header.
+const ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK: HeaderName =
+    HeaderName::from_static("access-control-allow-private-network");
+
+/// Allows any headers of the request, via [`Config::allow_headers`].
+#[derive(Debug, Clone, Copy)]
+pub struct Any;
+
+/// The allowed HTTP headers.
+#[derive(Debug, Clone)]
+pub enum AllowHeaders {
+    /// Mirrors the `Access-Control-Request-Headers` of the preflight request.
+    Any,
+    /// A list of HTTP headers.
+    List(HashSet<HeaderName>),
+}
+
+impl From<Any> for AllowHeaders {
+    fn from(_: Any) -> Self {
+        Self::Any
+    }
+}
+
+impl<H> From<H> for AllowHeaders
+where
+    H: IntoIterator,
+    H::Item: TryInto<HeaderName>,
+{
+    fn from(allow_headers: H) -> Self {
+        Self::List(
+            allow_headers
+                .into_iter()
+                .map(TryInto::try_into)
+                .filter_map(Result::ok)
+                .collect(),
+        )
+    }
+}
+
+/// An origin pattern with a wildcard, e.g. `https://*.example.com`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct OriginPattern {
+    prefix: String,
+    suffix: String,
+}
+
+impl OriginPattern {
+    fn parse(s: &str) -> Option<Self> {
+        let (prefix, suffix) = s.split_once('*')?;
+        if suffix.contains('*') {
+            return None;
+        }
+        Some(Self {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+        })
+    }
+
+    fn matches(&self, origin: &str) -> bool {
+        origin.len() > self.prefix.len() + self.suffix.len()
+            && origin.starts_with(&self.prefix)
+            && origin.ends_with(&self.suffix)
+            && origin[self.prefix.len()..origin.len() - self.suffix.len()]
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.')
+    }
+}
+
 /// A configuration for [`CorsMiddleware`].
 pub struct Config {
     max_age: usize,
     credentials: bool,
+    private_network: bool,
     allow_methods: HashSet<Method>,
-    allow_headers: HashSet<HeaderName>,
+    allow_headers: AllowHeaders,
     allow_origins: HashSet<HeaderValue>,
+    origin_patterns: HashSet<OriginPattern>,
     expose_headers: HashSet<HeaderName>,
     origin_verify: Option<Arc<dyn Fn(&HeaderValue) -> bool + Send + Sync>>,
 }
@@ -51,6 +132,15 @@ impl Config {
         self
     }
 
+    /// Whether to allow the requests from public networks to private networks. [WICG]
+    ///
+    /// [WICG]: https://wicg.github.io/private-network-access/
+    #[must_use]
+    pub fn private_network(mut self, private_network: bool) -> Self {
+        self.private_network = private_network;
+        self
+    }
+
     /// Allowed HTTP methods. [MDN]
     ///
     /// [MDN]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Access-Control-Allow-Methods
@@ -68,24 +158,20 @@ impl Config {
         self
     }
 
-    /// Allowed HTTP headers. [MDN]
+    /// Allowed HTTP headers, a list of headers or [`Any`]. [MDN]
     ///
     /// [MDN]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Access-Control-Allow-Headers
     #[must_use]
     pub fn allow_headers<H>(mut self, allow_headers: H) -> Self
     where
-        H: IntoIterator,
-        H::Item: TryInto<HeaderName>,
+        H: Into<AllowHeaders>,
     {
-        self.allow_headers = allow_headers
-            .into_iter()
-            .map(TryInto::try_into)
-            .filter_map(Result::ok)
-            .collect();
+        self.allow_headers = allow_headers.into();
         self
     }
 
-    /// Allowed origins. [MDN]
+    /// Allowed origins, `*` allows any origins and `https://*.example.com` allows the
+    /// subdomains. [MDN]
     ///
     /// [MDN]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Access-Control-Allow-Origin
     #[must_use]
@@ -94,10 +180,17 @@ impl Config {
         H: IntoIterator,
         H::Item: TryInto<HeaderValue>,
     {
-        self.allow_origins = allow_origins
+        let (patterns, origins): (HashSet<_>, HashSet<_>) = allow_origins
             .into_iter()
             .map(TryInto::try_into)
             .filter_map(Result::ok)
+            .partition(|origin: &HeaderValue| origin != "*" && origin.as_bytes().contains(&b'*'));
+        self.allow_origins = origins;
+        self.origin_patterns = patterns
+            .iter()
+            .map(HeaderValue::to_str)
+            .filter_map(Result::ok)
+            .filter_map(OriginPattern::parse)
             .collect();
         self
     }
@@ -128,6 +221,16 @@ impl Config {
         self.origin_verify = origin_verify;
         self
     }
+
+    /// Checks the origin is allowed or not.
+    fn is_allowed(&self, origin: &HeaderValue) -> bool {
+        (self.allow_origins.contains(&HeaderValue::from_static("*"))
+            || self.allow_origins.contains(origin)
+            || origin
+                .to_str()
+                .is_ok_and(|o| self.origin_patterns.iter().any(|p| p.matches(o))))
+            && self.origin_verify.as_ref().map_or(true, |f| (f)(origin))
+    }
 }
 
 impl Default for Config {
@@ -135,6 +238,7 @@ impl Default for Config {
         Self {
             max_age: 86400,
             credentials: false,
+            private_network: false,
             allow_methods: HashSet::from([
                 Method::GET,
                 Method::POST,
@@ -144,7 +248,8 @@ impl Default for Config {
                 Method::PATCH,
             ]),
             allow_origins: HashSet::from([HeaderValue::from_static("*")]),
-            allow_headers: HashSet::new(),
+            origin_patterns: HashSet::new(),
+            allow_headers: AllowHeaders::List(HashSet::new()),
             expose_headers: HashSet::new(),
             origin_verify: None,
         }
@@ -156,9 +261,11 @@ impl Clone for Config {
         Self {
             max_age: self.max_age,
             credentials: self.credentials,
+            private_network: self.private_network,
             allow_methods: self.allow_methods.clone(),
             allow_headers: self.allow_headers.clone(),
             allow_origins: self.allow_origins.clone(),
+            origin_patterns: self.origin_patterns.clone(),
             expose_headers: self.expose_headers.clone(),
             origin_verify: self.origin_verify.clone(),
         }
@@ -170,9 +277,11 @@ impl fmt::Debug for Config {
         f.debug_struct("CorsConfig")
             .field("max_age", &self.max_age)
             .field("credentials", &self.credentials)
+            .field("private_network", &self.private_network)
             .field("allow_methods", &self.allow_methods)
             .field("allow_headers", &self.allow_headers)
             .field("allow_origins", &self.allow_origins)
+            .field("origin_patterns", &self.origin_patterns)
             .field("expose_headers", &self.expose_headers)
             .finish_non_exhaustive()
     }
@@ -185,7 +294,10 @@ impl<H> Transform<H> for Config {
         CorsMiddleware {
             h,
             acam: self.allow_methods.clone().into_iter().collect(),
-            acah: self.allow_headers.clone().into_iter().collect(),
+            acah: match &self.allow_headers {
+                AllowHeaders::Any => AccessControlAllowHeaders::from_iter([]),
+                AllowHeaders::List(headers) => headers.clone().into_iter().collect(),
+            },
             aceh: self.expose_headers.clone().into_iter().collect(),
             config: self.clone(),
         }
@@ -215,13 +327,7 @@ where
             return self.h.call(req).await.map(IntoResponse::into_response);
         };
 
-        if !self.config.allow_origins.contains(&origin)
-            || !self
-                .config
-                .origin_verify
-                .as_ref()
-                .map_or(true, |f| (f)(&origin))
-        {
+        if !self.config.is_allowed(&origin) {
             return Err(StatusCode::FORBIDDEN.into_error());
         }
 
@@ -240,34 +346,43 @@ where
                 return Err((StatusCode::FORBIDDEN, "Invalid Preflight Request").into_error());
             }
 
-            let (allow_headers, request_headers) = req
-                .header(ACCESS_CONTROL_REQUEST_HEADERS)
-                .map_or((true, None), |hs: HeaderValue| {
-                    (
-                        hs.to_str()
-                            .map(|hs| {
-                                hs.split(',')
-                                    .map(str::as_bytes)
-                                    .map(HeaderName::from_bytes)
-                                    .filter_map(Result::ok)
-                                    .any(|header| self.config.allow_headers.contains(&header))
-                            })
-                            .unwrap_or(false),
-                        Some(hs),
-                    )
-                });
-
-            if !allow_headers {
-                return Err((StatusCode::FORBIDDEN, "Invalid Preflight Request").into_error());
+            let request_headers = req.header::<_, HeaderValue>(ACCESS_CONTROL_REQUEST_HEADERS);
+
+            match &self.config.allow_headers {
+                AllowHeaders::List(allow_headers) if !allow_headers.is_empty() => {
+                    if !request_headers.as_ref().map_or(true, |hs| {
+                        hs.to_str().is_ok_and(|hs| {
+                            hs.split(',')
+                                .map(str::trim)
+                                .filter(|h| !h.is_empty())
+                                .map(HeaderName::try_from)
+                                .all(|h| h.is_ok_and(|h| allow_headers.contains(&h)))
+                        })
+                    }) {
+                        return Err(
+                            (StatusCode::FORBIDDEN, "Invalid Preflight Request").into_error()
+                        );
+                    }
+                    headers.typed_insert(self.acah.clone());
+                }
+                // Mirrors the request headers
+                _ => {
+                    headers.insert(
+                        ACCESS_CONTROL_ALLOW_HEADERS,
+                        request_headers.unwrap_or(HeaderValue::from_static("*")),
+                    );
+                }
             }
 
-            if self.config.allow_headers.is_empty() {
+            if self.config.private_network
+                && req
+                    .header::<_, HeaderValue>(ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK)
+                    .is_some_and(|v| v == "true")
+            {
                 headers.insert(
-                    ACCESS_CONTROL_ALLOW_HEADERS,
-                    request_headers.unwrap_or(HeaderValue::from_static("*")),
+                    ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK,
+                    HeaderValue::from_static("true"),
                 );
-            } else {
-                headers.typed_insert(self.acah.clone());
             }
 
             // 204 - no content
@@ -5,18 +5,28 @@ use std::{collections::HashSet, fmt, sync::Arc};
 use base64::Engine as _;
 
 use crate::{
-    header::{HeaderName, HeaderValue, VARY},
+    header::{HeaderName, HeaderValue, HOST, ORIGIN, REFERER, VARY},
     middleware::helper::{CookieOptions, Cookieable},
-    Error, FromRequest, Handler, IntoResponse, Method, Request, RequestExt, Response, Result,
-    StatusCode, Transform,
+    Error, FromRequest, FromRequestParts, Handler, IntoResponse, Method, Request, RequestExt,
+    Response, Result, StatusCode, Transform,
 };
 
+#[cfg(any(feature = "form", feature = "multipart"))]
+use crate::{types::Payload, BodyState, Bytes};
+#[cfg(any(feature = "form", feature = "multipart"))]
+use http_body_util::Full;
+
+/// The original host which is forwarded by a proxy.
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+
 #[derive(Debug)]
 struct Inner<S, G, V> {
     store: Store,
     ignored_methods: HashSet<Method>,
     cookie_options: CookieOptions,
     header: HeaderName,
+    field: Option<&'static str>,
+    same_origin: bool,
     secret: S,
     generate: G,
     verify: V,
@@ -36,10 +46,10 @@ pub enum Store {
 #[derive(Debug, Clone)]
 pub struct CsrfToken(pub String);
 
-impl FromRequest for CsrfToken {
+impl FromRequestParts for CsrfToken {
     type Error = Error;
 
-    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
         req.extensions()
             .get()
             .cloned()
@@ -47,9 +57,21 @@ impl FromRequest for CsrfToken {
     }
 }
 
+impl FromRequest for CsrfToken {
+    type Error = Error;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        Self::extract_parts(req).await
+    }
+}
+
 /// A configuration for [`CsrfMiddleware`].
 pub struct Config<S, G, V>(Arc<Inner<S, G, V>>);
 
+/// A configuration with the built-in [`secret`], [`generate`] and [`verify`] functions.
+pub type DefaultConfig =
+    Config<fn() -> Result<Vec<u8>>, fn(&[u8], Vec<u8>) -> Vec<u8>, fn(&[u8], String) -> bool>;
+
 impl<S, G, V> Config<S, G, V>
 where
     S: Send + Sync,
@@ -76,9 +98,51 @@ where
             generate,
             verify,
             header: HeaderName::from_static(Self::CSRF_TOKEN),
+            field: None,
+            same_origin: false,
         }))
     }
 
+    /// Sets the name of the CSRF header, `x-csrf-token` by defaults.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the configuration has been shared.
+    #[must_use]
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.inner_mut().header = header;
+        self
+    }
+
+    /// Reads the CSRF token from the named field of an `application/x-www-form-urlencoded` or
+    /// `multipart/form-data` body when the header is missing.
+    ///
+    /// The body is buffered and restored, so the handler can still extract it.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the configuration has been shared.
+    #[must_use]
+    pub fn form_field(mut self, name: &'static str) -> Self {
+        self.inner_mut().field.replace(name);
+        self
+    }
+
+    /// Rejects the unsafe requests whose `Origin` or `Referer` does not match the `Host`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the configuration has been shared.
+    #[must_use]
+    pub fn same_origin(mut self, same_origin: bool) -> Self {
+        self.inner_mut().same_origin = same_origin;
+        self
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner<S, G, V> {
+        Arc::get_mut(&mut self.0).expect("csrf config should not be shared before building")
+    }
+
     /// Gets the CSRF token from cookies or session.
     ///
     /// # Errors
@@ -126,6 +190,19 @@ where
     }
 }
 
+impl Default for DefaultConfig {
+    fn default() -> Self {
+        Self::new(
+            Store::Cookie,
+            [Method::GET, Method::HEAD, Method::OPTIONS, Method::TRACE].into(),
+            CookieOptions::new("_csrf"),
+            secret,
+            generate,
+            verify,
+        )
+    }
+}
+
 impl<S, G, V> Clone for Config<S, G, V> {
     fn clone(&self) -> Self {
         Self(self.0.clone())
@@ -148,6 +225,8 @@ impl<S, G, V> fmt::Debug for Config<S, G, V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CsrfConfig")
             .field("header", &self.as_ref().header)
+            .field("field", &self.as_ref().field)
+            .field("same_origin", &self.as_ref().same_origin)
             .field("cookie_options", &self.as_ref().cookie_options)
             .field("ignored_methods", &self.as_ref().ignored_methods)
             .finish()
@@ -201,9 +280,20 @@ where
         let config = self.config.as_ref();
 
         if !config.ignored_methods.contains(req.method()) {
+            if config.same_origin && !is_same_origin(&req) {
+                return Err((StatusCode::FORBIDDEN, "Invalid origin").into_error());
+            }
+
             let mut forbidden = true;
             if let Some(secret) = secret.take() {
-                if let Some(raw_token) = req.header(&config.header) {
+                let raw_token = match req.header(&config.header) {
+                    Some(raw_token) => Some(raw_token),
+                    None => match config.field {
+                        Some(name) => read_field(&mut req, name).await?,
+                        None => None,
+                    },
+                };
+                if let Some(raw_token) = raw_token {
                     forbidden = !(config.verify)(&secret, raw_token);
                 }
             }
@@ -231,6 +321,109 @@ where
     }
 }
 
+/// Checks the `Origin` header, or the `Referer` header if missing, against the
+/// `X-Forwarded-Host` header behind a proxy or the `Host` header.
+///
+/// The default port of the scheme is the same as the missing port, e.g. `https://viz.rs:443`
+/// matches `viz.rs`.
+fn is_same_origin(req: &Request) -> bool {
+    let Some(origin) = req
+        .headers()
+        .get(ORIGIN)
+        .or_else(|| req.headers().get(REFERER))
+        .map(HeaderValue::to_str)
+        .and_then(Result::ok)
+        .map(str::parse::<http::Uri>)
+        .and_then(Result::ok)
+    else {
+        return false;
+    };
+    let (Some(authority), Some(scheme)) = (origin.authority(), origin.scheme_str()) else {
+        return false;
+    };
+    let default_port = match scheme {
+        "http" | "ws" => 80,
+        "https" | "wss" => 443,
+        _ => return false,
+    };
+    let port = authority.port_u16().unwrap_or(default_port);
+
+    [X_FORWARDED_HOST, HOST]
+        .into_iter()
+        .filter_map(|name| req.headers().get(name))
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(',').next())
+        .filter_map(|value| value.trim().parse::<http::uri::Authority>().ok())
+        .any(|host| {
+            host.host().eq_ignore_ascii_case(authority.host())
+                && host.port_u16().unwrap_or(default_port) == port
+        })
+}
+
+/// Reads a field from the form body, then puts the buffered body back into the request.
+#[cfg(any(feature = "form", feature = "multipart"))]
+async fn read_field(req: &mut Request, name: &str) -> Result<Option<String>> {
+    let Some(m) = req.content_type() else {
+        return Ok(None);
+    };
+
+    #[cfg(feature = "form")]
+    if <crate::types::Form as Payload>::detect(&m) {
+        let bytes = read_body::<crate::types::Form>(req).await?;
+        return serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+            .map(|pairs| pairs.into_iter().find(|(k, _)| k == name).map(|(_, v)| v))
+            .map_err(|e| crate::types::PayloadError::UrlDecode(e).into());
+    }
+
+    #[cfg(feature = "multipart")]
+    if <crate::types::Multipart as Payload>::detect(&m) {
+        use futures_util::TryStreamExt;
+
+        let boundary = m
+            .get_param(mime::BOUNDARY)
+            .ok_or(crate::types::PayloadError::MissingBoundary)?
+            .to_string();
+        let bytes = read_body::<crate::types::Multipart>(req).await?;
+        let mut form = crate::types::Multipart::new(crate::Body::from(Full::new(bytes)), &boundary);
+        while let Some(mut field) = form.try_next().await? {
+            if field.name == name && field.filename.is_none() {
+                let value = field.bytes().await?;
+                return Ok(String::from_utf8(value.to_vec()).ok());
+            }
+            field.ignore().await?;
+        }
+    }
+
+    Ok(None)
+}
+
+/// The form field can not be read without the `form` or `multipart` feature.
+#[cfg(not(any(feature = "form", feature = "multipart")))]
+#[allow(clippy::unused_async)]
+async fn read_field(_: &mut Request, _: &str) -> Result<Option<String>> {
+    Ok(None)
+}
+
+#[cfg(any(feature = "form", feature = "multipart"))]
+async fn read_body<P: Payload>(req: &mut Request) -> Result<Bytes> {
+    #[cfg(feature = "limits")]
+    let bytes = {
+        use crate::{types::Limits, RequestLimitsExt};
+        let limit = req
+            .extensions()
+            .get::<Limits>()
+            .and_then(|l| l.get(P::NAME));
+        req.bytes_with(limit, P::LIMIT).await?
+    };
+    #[cfg(not(feature = "limits"))]
+    let bytes = req.bytes().await?;
+
+    *req.body_mut() = Full::new(bytes.clone()).into();
+    req.extensions_mut().insert(BodyState::Normal);
+
+    Ok(bytes)
+}
+
 /// Gets random secret
 ///
 /// # Errors
@@ -299,5 +492,34 @@ mod tests {
             generate,
             verify,
         );
+
+        let config = DefaultConfig::default()
+            .header(HeaderName::from_static("x-xsrf-token"))
+            .form_field("_csrf")
+            .same_origin(true);
+        assert_eq!(config.as_ref().field, Some("_csrf"));
+        assert!(config.as_ref().same_origin);
+    }
+
+    #[test]
+    fn same_origin() {
+        let req = |origin: &str| {
+            Request::builder()
+                .header(HOST, "viz.rs")
+                .header(ORIGIN, origin)
+                .body(crate::Body::Empty)
+                .unwrap()
+        };
+
+        assert!(is_same_origin(&req("https://viz.rs")));
+        assert!(!is_same_origin(&req("https://evil.rs")));
+        assert!(!is_same_origin(&req("null")));
+
+        let req = Request::builder()
+            .header(HOST, "viz.rs")
+            .header(REFERER, "https://viz.rs/form")
+            .body(crate::Body::Empty)
+            .unwrap();
+        assert!(is_same_origin(&req));
     }
 }
@@ -0,0 +1,177 @@
+//! IP Filter Middleware.
+//!
+//! Allows or denies the requests by the client IP, which is matched against the CIDR ranges.
+//!
+//! The deny list is checked first, then the allow list, an empty allow list allows all.
+
+use std::{
+    net::IpAddr,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use crate::{Handler, IntoResponse, Request, RequestExt, Response, Result, StatusCode, Transform};
+
+pub use crate::types::{Cidr, CidrError};
+
+#[derive(Debug, Default)]
+struct Rules {
+    allow: Vec<Cidr>,
+    deny: Vec<Cidr>,
+}
+
+impl Rules {
+    fn verify(&self, ip: Option<IpAddr>) -> bool {
+        match ip {
+            Some(ip) => {
+                !self.deny.iter().any(|cidr| cidr.contains(&ip))
+                    && (self.allow.is_empty() || self.allow.iter().any(|cidr| cidr.contains(&ip)))
+            }
+            None => self.allow.is_empty(),
+        }
+    }
+}
+
+/// A shared handle for reloading the rules at runtime.
+#[derive(Debug, Clone)]
+pub struct Handle(Arc<RwLock<Rules>>);
+
+impl Handle {
+    /// Replaces the allow list.
+    pub fn allow<I>(&self, allow: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<Cidr>,
+    {
+        self.0.write().unwrap_or_else(PoisonError::into_inner).allow =
+            allow.into_iter().map(Into::into).collect();
+    }
+
+    /// Replaces the deny list.
+    pub fn deny<I>(&self, deny: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<Cidr>,
+    {
+        self.0.write().unwrap_or_else(PoisonError::into_inner).deny =
+            deny.into_iter().map(Into::into).collect();
+    }
+}
+
+/// A configuration for [`IpFilterMiddleware`].
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    rules: Arc<RwLock<Rules>>,
+    realip: bool,
+}
+
+impl Config {
+    /// Creates a new Config.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the allowed CIDR ranges.
+    #[must_use]
+    pub fn allow<I>(self, allow: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Cidr>,
+    {
+        self.handle().allow(allow);
+        self
+    }
+
+    /// Sets the denied CIDR ranges.
+    #[must_use]
+    pub fn deny<I>(self, deny: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Cidr>,
+    {
+        self.handle().deny(deny);
+        self
+    }
+
+    /// Uses the [`RealIp`][crate::types::RealIp] from the proxy headers instead of the remote
+    /// address, only enables it behind a trusted proxy.
+    #[must_use]
+    pub fn realip(mut self, realip: bool) -> Self {
+        self.realip = realip;
+        self
+    }
+
+    /// Gets a handle for reloading the rules at runtime.
+    #[must_use]
+    pub fn handle(&self) -> Handle {
+        Handle(self.rules.clone())
+    }
+}
+
+impl<H> Transform<H> for Config
+where
+    H: Clone,
+{
+    type Output = IpFilterMiddleware<H>;
+
+    fn transform(&self, h: H) -> Self::Output {
+        IpFilterMiddleware {
+            h,
+            config: self.clone(),
+        }
+    }
+}
+
+/// IP Filter middleware.
+#[derive(Debug, Clone)]
+pub struct IpFilterMiddleware<H> {
+    h: H,
+    config: Config,
+}
+
+#[crate::async_trait]
+impl<H, O> Handler<Request> for IpFilterMiddleware<H>
+where
+    H: Handler<Request, Output = Result<O>>,
+    O: IntoResponse,
+{
+    type Output = Result<Response>;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let ip = if self.config.realip {
+            req.realip().map(|ip| ip.0)
+        } else {
+            req.remote_addr().map(std::net::SocketAddr::ip)
+        };
+
+        if !self
+            .config
+            .rules
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .verify(ip)
+        {
+            return Err(StatusCode::FORBIDDEN.into_error());
+        }
+
+        self.h.call(req).await.map(IntoResponse::into_response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules() {
+        let config = Config::new()
+            .allow(["10.0.0.0/8".parse::<Cidr>().unwrap()])
+            .deny(["10.0.0.1".parse::<Cidr>().unwrap()]);
+        let rules = config.rules.read().unwrap();
+
+        assert!(rules.verify(Some("10.0.0.2".parse().unwrap())));
+        assert!(!rules.verify(Some("10.0.0.1".parse().unwrap())));
+        assert!(!rules.verify(Some("192.168.0.1".parse().unwrap())));
+        assert!(!rules.verify(None));
+    }
+}
@@ -0,0 +1,399 @@
+//! Logger Middleware.
+//!
+//! Emits an access log record per request, in the [Common Log Format], the Combined Log Format
+//! or JSON. The records are written to [`tracing`] with the `viz::logger` target by default.
+//!
+//! [Common Log Format]: https://httpd.apache.org/docs/current/logs.html#common
+
+use std::{
+    fmt,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    header::{REFERER, USER_AGENT},
+    types::Cidr,
+    Error, Handler, IntoResponse, Method, Request, RequestExt, Response, ResponseExt, Result,
+    StatusCode, Transform,
+};
+
+/// The output format of the access log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// The Common Log Format.
+    #[default]
+    Common,
+    /// The Combined Log Format, appends the referer and user agent to the Common Log Format.
+    Combined,
+    /// A JSON object per line.
+    Json,
+}
+
+/// An access log record.
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// The time of the request received.
+    pub time: SystemTime,
+    /// The HTTP method.
+    pub method: Method,
+    /// The request URI's path and query.
+    pub uri: String,
+    /// The matched route pattern.
+    pub route: Option<String>,
+    /// The HTTP version.
+    pub version: http::Version,
+    /// The response status.
+    pub status: StatusCode,
+    /// The handling duration.
+    pub latency: Duration,
+    /// The request body size.
+    pub bytes_in: Option<u64>,
+    /// The response body size.
+    pub bytes_out: Option<u64>,
+    /// The client IP, which is the remote address, or the [`RealIp`] from the proxy headers
+    /// behind a trusted proxy.
+    ///
+    /// [`RealIp`]: crate::types::RealIp
+    pub realip: Option<IpAddr>,
+    /// The `User-Agent` header.
+    pub user_agent: Option<String>,
+    /// The `Referer` header.
+    pub referer: Option<String>,
+    /// The request ID.
+    pub request_id: Option<String>,
+}
+
+impl Record {
+    /// Formats the record as a line.
+    #[must_use]
+    pub fn format(&self, format: Format) -> String {
+        match format {
+            Format::Common => self.to_string(),
+            Format::Combined => format!(
+                "{} \"{}\" \"{}\"",
+                self,
+                self.referer.as_deref().unwrap_or("-"),
+                self.user_agent.as_deref().unwrap_or("-")
+            ),
+            Format::Json => serde_json::json!({
+                "time": rfc3339(self.time),
+                "method": self.method.as_str(),
+                "uri": self.uri,
+                "route": self.route,
+                "version": format!("{:?}", self.version),
+                "status": self.status.as_u16(),
+                "latency_ms": self.latency.as_secs_f64() * 1000.0,
+                "bytes_in": self.bytes_in,
+                "bytes_out": self.bytes_out,
+                "realip": self.realip,
+                "user_agent": self.user_agent,
+                "referer": self.referer,
+                "request_id": self.request_id,
+            })
+            .to_string(),
+        }
+    }
+}
+
+/// Formats the record in the Common Log Format.
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} - - [{}] \"{} {} {:?}\" {} ",
+            self.realip
+                .as_ref()
+                .map_or_else(|| "-".to_string(), ToString::to_string),
+            clf(self.time),
+            self.method,
+            self.uri,
+            self.version,
+            self.status.as_u16(),
+        )?;
+        match self.bytes_out {
+            Some(size) => write!(f, "{size}"),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+/// A configuration for [`LoggerMiddleware`].
+#[derive(Clone, Default)]
+pub struct Config {
+    format: Format,
+    writer: Option<Arc<dyn Fn(&Record, &str) + Send + Sync>>,
+    skip: Option<Arc<dyn Fn(&Request) -> bool + Send + Sync>>,
+    trusted_proxies: Vec<Cidr>,
+}
+
+impl Config {
+    /// Creates a new Config.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the output format.
+    #[must_use]
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets a custom writer instead of [`tracing`], receives the record and the formatted line.
+    #[must_use]
+    pub fn writer<F>(mut self, writer: F) -> Self
+    where
+        F: Fn(&Record, &str) + Send + Sync + 'static,
+    {
+        self.writer = Some(Arc::new(writer));
+        self
+    }
+
+    /// Skips the matched requests, e.g. health checks.
+    #[must_use]
+    pub fn skip<F>(mut self, skip: F) -> Self
+    where
+        F: Fn(&Request) -> bool + Send + Sync + 'static,
+    {
+        self.skip = Some(Arc::new(skip));
+        self
+    }
+
+    /// Trusts the proxy headers, e.g. `X-Forwarded-For`, if the remote address is in the
+    /// trusted proxies.
+    #[must_use]
+    pub fn trusted_proxies<I>(mut self, proxies: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Cidr>,
+    {
+        self.trusted_proxies = proxies.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The proxy headers can be spoofed by the clients, only trusts them behind a trusted proxy.
+    fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        let remote = req.remote_addr().map(SocketAddr::ip);
+        if remote.is_some_and(|ip| self.trusted_proxies.iter().any(|cidr| cidr.contains(&ip))) {
+            return req.realip().map(|ip| ip.0).or(remote);
+        }
+        remote
+    }
+
+    fn write(&self, record: &Record) {
+        let line = record.format(self.format);
+        if let Some(writer) = &self.writer {
+            (writer)(record, &line);
+        } else {
+            tracing::info!(
+                target: "viz::logger",
+                method = %record.method,
+                route = record.route.as_deref(),
+                status = record.status.as_u16(),
+                latency_ms = record.latency.as_secs_f64() * 1000.0,
+                bytes_in = record.bytes_in,
+                bytes_out = record.bytes_out,
+                realip = record.realip.map(tracing::field::display),
+                user_agent = record.user_agent.as_deref(),
+                request_id = record.request_id.as_deref(),
+                "{line}"
+            );
+        }
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoggerConfig")
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H> Transform<H> for Config
+where
+    H: Clone,
+{
+    type Output = LoggerMiddleware<H>;
+
+    fn transform(&self, h: H) -> Self::Output {
+        LoggerMiddleware {
+            h,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Logger middleware.
+#[derive(Debug, Clone)]
+pub struct LoggerMiddleware<H> {
+    h: H,
+    config: Config,
+}
+
+#[crate::async_trait]
+impl<H, O> Handler<Request> for LoggerMiddleware<H>
+where
+    H: Handler<Request, Output = Result<O>>,
+    O: IntoResponse,
+{
+    type Output = Result<Response>;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        if self.config.skip.as_ref().is_some_and(|skip| (skip)(&req)) {
+            return self.h.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let time = SystemTime::now();
+        let start = Instant::now();
+        let mut record = Record {
+            time,
+            method: req.method().clone(),
+            uri: req
+                .uri()
+                .path_and_query()
+                .map_or_else(|| req.path().to_string(), ToString::to_string),
+            route: None,
+            version: req.version(),
+            status: StatusCode::OK,
+            latency: Duration::ZERO,
+            bytes_in: req.content_length(),
+            bytes_out: None,
+            realip: self.config.client_ip(&req),
+            user_agent: header(&req, USER_AGENT),
+            referer: header(&req, REFERER),
+            request_id: None,
+        };
+
+        #[cfg(feature = "params")]
+        {
+            record.route = req
+                .extensions()
+                .get::<Arc<crate::types::RouteInfo>>()
+                .map(|info| info.pattern.clone());
+        }
+
+        // Set by the `request_id` middleware, which should wrap this middleware.
+        #[cfg(feature = "request-id")]
+        {
+            record.request_id = req
+                .extensions()
+                .get::<super::request_id::RequestId>()
+                .map(ToString::to_string);
+        }
+
+        let resp = self.h.call(req).await.map(IntoResponse::into_response);
+
+        record.latency = start.elapsed();
+        let resp = match resp {
+            Ok(resp) => {
+                record.status = resp.status();
+                record.bytes_out = resp.content_length();
+                Ok(resp)
+            }
+            Err(err) => {
+                record.status = match &err {
+                    Error::Responder(resp) | Error::Report(_, resp) => resp.status(),
+                    Error::Boxed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                Err(err)
+            }
+        };
+
+        self.config.write(&record);
+
+        resp
+    }
+}
+
+fn header(req: &Request, name: crate::header::HeaderName) -> Option<String> {
+    req.headers()
+        .get(name)
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+}
+
+/// Splits the time into the UTC date and time parts.
+fn civil(time: SystemTime) -> (i64, u32, u32, u64, u64, u64) {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let days = i64::try_from(secs / 86400).unwrap_or_default();
+    let rem = secs % 86400;
+
+    // <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = u32::try_from(doy - (153 * mp + 2) / 5 + 1).unwrap_or_default();
+    let month = u32::try_from(if mp < 10 { mp + 3 } else { mp - 9 }).unwrap_or_default();
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// Formats the time as `10/Oct/2000:13:55:36 +0000`.
+fn clf(time: SystemTime) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (year, month, day, hour, min, sec) = civil(time);
+    let month = usize::try_from(month)
+        .ok()
+        .and_then(|month| month.checked_sub(1))
+        .and_then(|month| MONTHS.get(month))
+        .unwrap_or(&"-");
+    format!("{day:02}/{month}/{year}:{hour:02}:{min:02}:{sec:02} +0000")
+}
+
+/// Formats the time as `2000-10-10T13:55:36Z`.
+fn rfc3339(time: SystemTime) -> String {
+    let (year, month, day, hour, min, sec) = civil(time);
+    format!("{year}-{month:02}-{day:02}T{hour:02}:{min:02}:{sec:02}Z")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times() {
+        let time = UNIX_EPOCH + Duration::from_secs(971_186_136);
+        assert_eq!(clf(time), "10/Oct/2000:13:55:36 +0000");
+        assert_eq!(rfc3339(time), "2000-10-10T13:55:36Z");
+
+        let time = UNIX_EPOCH + Duration::from_secs(1_709_210_096);
+        assert_eq!(rfc3339(time), "2024-02-29T12:34:56Z");
+    }
+
+    #[test]
+    fn formats() {
+        let record = Record {
+            time: UNIX_EPOCH + Duration::from_secs(971_186_136),
+            method: Method::GET,
+            uri: "/apache_pb.gif".to_string(),
+            route: None,
+            version: http::Version::HTTP_10,
+            status: StatusCode::OK,
+            latency: Duration::from_millis(1),
+            bytes_in: None,
+            bytes_out: Some(2326),
+            realip: "127.0.0.1".parse().ok(),
+            user_agent: Some("Mozilla/4.08".to_string()),
+            referer: None,
+            request_id: None,
+        };
+
+        assert_eq!(
+            record.format(Format::Common),
+            r#"127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] "GET /apache_pb.gif HTTP/1.0" 200 2326"#
+        );
+        assert_eq!(
+            record.format(Format::Combined),
+            r#"127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] "GET /apache_pb.gif HTTP/1.0" 200 2326 "-" "Mozilla/4.08""#
+        );
+    }
+}
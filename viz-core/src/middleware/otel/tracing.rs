@@ -24,6 +24,10 @@ use crate::{
     Handler, IntoResponse, Request, RequestExt, Response, ResponseExt, Result, Transform,
 };
 
+/// The request ID attribute.
+#[cfg(feature = "request-id")]
+const HTTP_REQUEST_ID: &str = "http.request.id";
+
 /// `OpenTelemetry` tracing config.
 #[derive(Debug)]
 pub struct Config<T> {
@@ -222,5 +226,14 @@ fn build_attributes(req: &Request, http_route: &str) -> Vec<KeyValue> {
         attributes.push(KeyValue::new(USER_AGENT_ORIGINAL, user_agent.to_string()));
     }
 
+    // Set by the `request_id` middleware, which should wrap this middleware.
+    #[cfg(feature = "request-id")]
+    if let Some(request_id) = req
+        .extensions()
+        .get::<crate::middleware::request_id::RequestId>()
+    {
+        attributes.push(KeyValue::new(HTTP_REQUEST_ID, request_id.to_string()));
+    }
+
     attributes
 }
@@ -0,0 +1,238 @@
+//! Problem Details Middleware.
+//!
+//! Renders the errors as the [problem details] by the `Accept` header of the request,
+//! `application/problem+json` by default, or falls back to the plain text or HTML.
+//!
+//! The error responses which already have a non-text `Content-Type` are kept as is.
+//!
+//! [problem details]: https://www.rfc-editor.org/rfc/rfc9457
+
+use http_body_util::BodyExt;
+
+use crate::{
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    types::{Accept, Problem},
+    Body, BoxError, Error, Handler, IntoResponse, Request, Response, ResponseExt, Result,
+    StatusCode, Transform,
+};
+
+/// The representation of the problem details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Problem,
+    Html,
+    Text,
+}
+
+/// A configuration for [`ProblemMiddleware`].
+#[derive(Debug, Clone)]
+pub struct Config {
+    type_base: Option<String>,
+    instance: bool,
+    expose_internal: bool,
+}
+
+impl Config {
+    /// Creates a new Config.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the base URI of the problem types, the status code is appended to it,
+    /// e.g. `https://viz.rs/problems/` becomes `https://viz.rs/problems/404`.
+    #[must_use]
+    pub fn type_base<S>(mut self, base: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.type_base = Some(base.into());
+        self
+    }
+
+    /// Sets the request path as the instance or not, `true` by default.
+    #[must_use]
+    pub fn instance(mut self, instance: bool) -> Self {
+        self.instance = instance;
+        self
+    }
+
+    /// Exposes the messages of the internal errors as the detail or not, `false` by default.
+    #[must_use]
+    pub fn expose_internal(mut self, expose: bool) -> Self {
+        self.expose_internal = expose;
+        self
+    }
+
+    /// Renders the error as the problem details, the source of the error is kept for reporting.
+    async fn problem(&self, err: Error) -> Result<(Problem, Option<Response>, Option<BoxError>)> {
+        let (source, resp) = match err {
+            Error::Boxed(e) => {
+                let detail = self.expose_internal.then(|| e.to_string());
+                let problem = self.new_problem(StatusCode::INTERNAL_SERVER_ERROR, detail);
+                return Ok((problem, None, Some(e)));
+            }
+            Error::Responder(resp) => (None, resp),
+            Error::Report(e, resp) => (Some(e), resp),
+        };
+
+        let is_text = resp.headers().get(CONTENT_TYPE).map_or(true, |value| {
+            value
+                .as_bytes()
+                .starts_with(mime::TEXT_PLAIN.as_ref().as_bytes())
+        });
+        if resp.status().as_u16() < 400
+            || !is_text
+            || !matches!(resp.body(), Body::Empty | Body::Full(_))
+        {
+            return Err(match source {
+                Some(e) => Error::Report(e, resp),
+                None => Error::Responder(resp),
+            });
+        }
+
+        let (parts, body) = resp.into_parts();
+        let bytes = body.collect().await?.to_bytes();
+        let detail = (!bytes.is_empty())
+            .then(|| String::from_utf8_lossy(&bytes).into_owned())
+            .filter(|_| parts.status != StatusCode::INTERNAL_SERVER_ERROR || self.expose_internal);
+        let problem = self.new_problem(parts.status, detail);
+
+        Ok((
+            problem,
+            Some(Response::from_parts(parts, Body::Empty)),
+            source,
+        ))
+    }
+
+    fn new_problem(&self, status: StatusCode, detail: Option<String>) -> Problem {
+        let mut problem = Problem::new(status);
+        problem.detail = detail;
+        if let Some(base) = &self.type_base {
+            problem.type_uri = format!("{base}{}", status.as_u16());
+        }
+        problem
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            type_base: None,
+            instance: true,
+            expose_internal: false,
+        }
+    }
+}
+
+impl<H> Transform<H> for Config
+where
+    H: Clone,
+{
+    type Output = ProblemMiddleware<H>;
+
+    fn transform(&self, h: H) -> Self::Output {
+        ProblemMiddleware {
+            h,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Problem Details middleware.
+#[derive(Debug, Clone)]
+pub struct ProblemMiddleware<H> {
+    h: H,
+    config: Config,
+}
+
+#[crate::async_trait]
+impl<H, O> Handler<Request> for ProblemMiddleware<H>
+where
+    H: Handler<Request, Output = Result<O>>,
+    O: IntoResponse,
+{
+    type Output = Result<Response>;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let format = negotiate(&Accept::from_headers(req.headers()));
+        let path = req.uri().path().to_string();
+
+        let err = match self.h.call(req).await {
+            Ok(resp) => return Ok(resp.into_response()),
+            Err(err) => err,
+        };
+
+        // Passes through the errors which are not rendered.
+        let (mut problem, original, source) = self.config.problem(err).await?;
+        if self.config.instance {
+            problem.instance = Some(path);
+        }
+
+        let status = problem.status;
+        let mut resp = match format {
+            Format::Problem => problem.into_response(),
+            Format::Html => Response::html(problem.to_html()),
+            Format::Text => Response::text(problem.to_text()),
+        };
+        *resp.status_mut() = status;
+
+        // Keeps the other headers of the error response, e.g. `Allow` or `WWW-Authenticate`.
+        if let Some(original) = original {
+            for (name, value) in original.headers() {
+                if name != CONTENT_TYPE && name != CONTENT_LENGTH {
+                    resp.headers_mut().insert(name, value.clone());
+                }
+            }
+        }
+
+        Err(match source {
+            Some(e) => Error::Report(e, resp),
+            None => Error::Responder(resp),
+        })
+    }
+}
+
+/// Picks the representation with the highest quality, the problem details are preferred.
+fn negotiate(accept: &Accept) -> Format {
+    let available = [
+        Problem::CONTENT_TYPE
+            .parse()
+            .unwrap_or(mime::APPLICATION_JSON),
+        mime::APPLICATION_JSON,
+        mime::TEXT_HTML,
+        mime::TEXT_PLAIN,
+    ];
+    match accept.negotiate(&available).map(mime::Mime::subtype) {
+        Some(mime::HTML) => Format::Html,
+        Some(mime::PLAIN) => Format::Text,
+        _ => Format::Problem,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::{HeaderMap, HeaderValue, ACCEPT};
+
+    #[test]
+    fn negotiation() {
+        let accept = |s: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(ACCEPT, HeaderValue::from_static(s));
+            negotiate(&Accept::from_headers(&headers))
+        };
+
+        assert_eq!(negotiate(&Accept::default()), Format::Problem);
+        assert_eq!(accept("*/*"), Format::Problem);
+        assert_eq!(accept("application/json"), Format::Problem);
+        assert_eq!(
+            accept("text/html,application/xhtml+xml,*/*;q=0.8"),
+            Format::Html
+        );
+        assert_eq!(accept("text/plain"), Format::Text);
+        assert_eq!(accept("text/*;q=0.5, application/json;q=0.4"), Format::Html);
+        assert_eq!(accept("text/html;q=0, */*;q=0.1"), Format::Problem);
+        assert_eq!(accept("image/png"), Format::Problem);
+    }
+}
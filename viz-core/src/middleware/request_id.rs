@@ -0,0 +1,264 @@
+//! Request ID Middleware.
+//!
+//! Reads the request ID from the `X-Request-Id` header or generates a new one, then stores it
+//! as [`RequestId`] and echoes it on the response.
+//!
+//! A boxed error is reported with an empty `500` response to carry the header, the error is
+//! kept for the outer middleware, e.g. the error handler, to render it.
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tracing::Instrument;
+
+use crate::{
+    header::{HeaderName, HeaderValue},
+    Error, FromRequest, FromRequestParts, Handler, IntoResponse, Request, Response, Result,
+    StatusCode, Transform,
+};
+
+/// The max length of the request ID from the header.
+const MAX_LEN: usize = 128;
+
+/// Extracts the request ID from the extensions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Gets the request ID as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromRequestParts for RequestId {
+    type Error = Error;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        req.extensions()
+            .get()
+            .cloned()
+            .ok_or_else(|| (StatusCode::INTERNAL_SERVER_ERROR, "Missing request id").into_error())
+    }
+}
+
+impl FromRequest for RequestId {
+    type Error = Error;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        Self::extract_parts(req).await
+    }
+}
+
+/// A configuration for [`RequestIdMiddleware`].
+#[derive(Clone)]
+pub struct Config {
+    header: HeaderName,
+    generator: Arc<dyn Fn() -> String + Send + Sync>,
+}
+
+impl Config {
+    /// The name of request ID header.
+    pub const X_REQUEST_ID: &'static str = "x-request-id";
+
+    /// Creates a new Config with the [`uuid`] generator.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the header name of the request ID.
+    #[must_use]
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the generator of the request ID, e.g. [`uuid`], [`ulid`] and [`nano_id`].
+    #[must_use]
+    pub fn generator<F>(mut self, generator: F) -> Self
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        self.generator = Arc::new(generator);
+        self
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            header: HeaderName::from_static(Self::X_REQUEST_ID),
+            generator: Arc::new(uuid),
+        }
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestIdConfig")
+            .field("header", &self.header)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H> Transform<H> for Config
+where
+    H: Clone,
+{
+    type Output = RequestIdMiddleware<H>;
+
+    fn transform(&self, h: H) -> Self::Output {
+        RequestIdMiddleware {
+            h,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Request ID middleware.
+#[derive(Debug, Clone)]
+pub struct RequestIdMiddleware<H> {
+    h: H,
+    config: Config,
+}
+
+#[crate::async_trait]
+impl<H, O> Handler<Request> for RequestIdMiddleware<H>
+where
+    H: Handler<Request, Output = Result<O>>,
+    O: IntoResponse,
+{
+    type Output = Result<Response>;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        let id = req
+            .headers()
+            .get(&self.config.header)
+            .filter(|value| is_valid(value))
+            .map(HeaderValue::to_str)
+            .and_then(Result::ok)
+            .map_or_else(|| (self.config.generator)(), ToString::to_string);
+        let value = HeaderValue::from_str(&id).map_err(Error::boxed)?;
+
+        req.extensions_mut().insert(RequestId(id.clone()));
+
+        let span = tracing::info_span!("request", request_id = %id);
+
+        match self.h.call(req).instrument(span).await {
+            Ok(resp) => {
+                let mut resp = resp.into_response();
+                resp.headers_mut().insert(self.config.header.clone(), value);
+                Ok(resp)
+            }
+            Err(err) => {
+                // The error is kept for the outer middleware, e.g. the error handler.
+                let mut err = err.into_report();
+                if let Error::Responder(resp) | Error::Report(_, resp) = &mut err {
+                    resp.headers_mut().insert(self.config.header.clone(), value);
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Only accepts the visible ASCII characters with a limited length.
+fn is_valid(value: &HeaderValue) -> bool {
+    !value.is_empty() && value.len() <= MAX_LEN && value.as_bytes().iter().all(u8::is_ascii_graphic)
+}
+
+/// Fills a random bytes.
+fn random<const N: usize>() -> [u8; N] {
+    let mut buf = [0u8; N];
+    getrandom::getrandom(&mut buf).expect("failed to generate random bytes");
+    buf
+}
+
+/// Generates a random [UUID] v4, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+///
+/// [UUID]: https://www.rfc-editor.org/rfc/rfc9562
+#[must_use]
+pub fn uuid() -> String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+
+    let mut buf = random::<16>();
+    buf[6] = (buf[6] & 0x0f) | 0x40;
+    buf[8] = (buf[8] & 0x3f) | 0x80;
+
+    let mut s = String::with_capacity(36);
+    for (i, b) in buf.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            s.push('-');
+        }
+        s.push(char::from(HEX[usize::from(b >> 4)]));
+        s.push(char::from(HEX[usize::from(b & 0x0f)]));
+    }
+    s
+}
+
+/// Generates a [ULID], e.g. `01ARZ3NDEKTSV4RRFFQ69G5FAV`.
+///
+/// [ULID]: https://github.com/ulid/spec
+#[must_use]
+pub fn ulid() -> String {
+    const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis())
+        & 0xffff_ffff_ffff;
+    let value = (timestamp << 80) | (u128::from_be_bytes(random::<16>()) >> 48);
+
+    (0..26)
+        .rev()
+        .map(|i| char::from(ALPHABET[((value >> (i * 5)) & 0x1f) as usize]))
+        .collect()
+}
+
+/// Generates a [nano ID], e.g. `V1StGXR8_Z5jdHi6B-myT`.
+///
+/// [nano ID]: https://github.com/ai/nanoid
+#[must_use]
+pub fn nano_id() -> String {
+    const ALPHABET: &[u8; 64] = b"_-0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    random::<21>()
+        .iter()
+        .map(|b| char::from(ALPHABET[usize::from(b & 63)]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generators() {
+        let id = uuid();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+
+        let id = ulid();
+        assert_eq!(id.len(), 26);
+        assert!(id.as_str() < "80000000000000000000000000");
+
+        let id = nano_id();
+        assert_eq!(id.len(), 21);
+
+        assert!(is_valid(&HeaderValue::from_static("abc-123")));
+        assert!(!is_valid(&HeaderValue::from_static("")));
+        assert!(!is_valid(&HeaderValue::from_static("a b")));
+    }
+}
@@ -0,0 +1,767 @@
+//! [`OpenAPI`] 3.1 document generation.
+//!
+//! An [`Operation`] is described by the extractors and the responders, the schemas are derived
+//! via [`JsonSchema`]. The operation of a handler with extractors is derived from its types by
+//! the [`OperationHandler`].
+//!
+//! [`OpenAPI`]: https://spec.openapis.org/oas/v3.1.0
+
+use std::fmt;
+
+pub use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde_json::{json, Map, Value};
+
+use crate::{Method, StatusCode};
+
+/// Adds the parameters or the request body into an operation object.
+type Input = fn(&mut SchemaGenerator, &mut Map<String, Value>, &[String]);
+
+/// Creates a response object.
+type Output = fn(&mut SchemaGenerator) -> Value;
+
+/// Describes the parameters or the request body of an operation by an extractor.
+///
+/// The extractors which add nothing into the document use the default, e.g.
+/// `impl OperationInput for MyExtractor {}`.
+pub trait OperationInput {
+    /// Adds the parameters or the request body into the operation object, the `path_params` are
+    /// the names of the parameters in the route path.
+    fn input(
+        _generator: &mut SchemaGenerator,
+        _operation: &mut Map<String, Value>,
+        _path_params: &[String],
+    ) {
+    }
+}
+
+/// Describes a response of an operation by a responder.
+///
+/// The responders without a content use the default, e.g. `impl OperationOutput for MyReply {}`.
+pub trait OperationOutput {
+    /// Creates a response object.
+    fn output(_generator: &mut SchemaGenerator) -> Value {
+        json!({})
+    }
+}
+
+/// Derives an [`Operation`] from the extractors and the responder of a handler.
+///
+/// It is implemented for the functions which arguments are [`OperationInput`] and the output is
+/// [`OperationOutput`].
+pub trait OperationHandler<E> {
+    /// Creates the operation.
+    fn operation() -> Operation;
+}
+
+/// The metadata about the API.
+#[derive(Debug, Clone)]
+pub struct Info {
+    title: String,
+    version: String,
+    description: Option<String>,
+}
+
+impl Info {
+    /// Creates a new info with the title and the version of the API.
+    pub fn new<T, V>(title: T, version: V) -> Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            title: title.into(),
+            version: version.into(),
+            description: None,
+        }
+    }
+
+    /// Sets the description of the API.
+    #[must_use]
+    pub fn description<D>(mut self, description: D) -> Self
+    where
+        D: Into<String>,
+    {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+#[derive(Clone)]
+enum Reply {
+    Output(Output),
+    Description(String),
+}
+
+/// The metadata of an operation.
+#[allow(clippy::struct_field_names)]
+#[derive(Clone, Default)]
+pub struct Operation {
+    summary: Option<String>,
+    description: Option<String>,
+    operation_id: Option<String>,
+    tags: Vec<String>,
+    deprecated: bool,
+    hidden: bool,
+    inputs: Vec<Input>,
+    responses: Vec<(StatusCode, Reply)>,
+}
+
+impl Operation {
+    /// Creates a new operation.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the summary.
+    #[must_use]
+    pub fn summary<S>(mut self, summary: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Sets the description.
+    #[must_use]
+    pub fn description<S>(mut self, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the unique ID.
+    #[must_use]
+    pub fn operation_id<S>(mut self, operation_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.operation_id = Some(operation_id.into());
+        self
+    }
+
+    /// Adds a tag.
+    #[must_use]
+    pub fn tag<S>(mut self, tag: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Marks the operation as deprecated.
+    #[must_use]
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
+    /// Excludes the operation from the document.
+    #[must_use]
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Checks the operation is excluded from the document or not.
+    #[must_use]
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Describes the parameters or the request body by an extractor, e.g. `Json<T>`,
+    /// `Query<T>`, `Params<T>` and `Form<T>`.
+    #[must_use]
+    pub fn input<T>(mut self) -> Self
+    where
+        T: OperationInput,
+    {
+        self.inputs.push(T::input);
+        self
+    }
+
+    /// Describes the `200 OK` response by a responder, e.g. `Json<T>`.
+    #[must_use]
+    pub fn output<T>(self) -> Self
+    where
+        T: OperationOutput,
+    {
+        self.response::<T>(StatusCode::OK)
+    }
+
+    /// Describes a response with the status by a responder.
+    #[must_use]
+    pub fn response<T>(mut self, status: StatusCode) -> Self
+    where
+        T: OperationOutput,
+    {
+        self.responses.push((status, Reply::Output(T::output)));
+        self
+    }
+
+    /// Describes a response with the status and description only.
+    #[must_use]
+    pub fn status<S>(mut self, status: StatusCode, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.responses
+            .push((status, Reply::Description(description.into())));
+        self
+    }
+
+    fn to_value(&self, generator: &mut SchemaGenerator, path_params: &[String]) -> Value {
+        let mut operation = Map::new();
+
+        if !self.tags.is_empty() {
+            operation.insert("tags".to_string(), json!(self.tags));
+        }
+        if let Some(summary) = &self.summary {
+            operation.insert("summary".to_string(), json!(summary));
+        }
+        if let Some(description) = &self.description {
+            operation.insert("description".to_string(), json!(description));
+        }
+        if let Some(operation_id) = &self.operation_id {
+            operation.insert("operationId".to_string(), json!(operation_id));
+        }
+
+        for input in &self.inputs {
+            (input)(generator, &mut operation, path_params);
+        }
+
+        // The path parameters are required, adds the missing ones.
+        let missing = path_params
+            .iter()
+            .filter(|name| {
+                !operation
+                    .get("parameters")
+                    .and_then(Value::as_array)
+                    .is_some_and(|parameters| {
+                        parameters
+                            .iter()
+                            .any(|p| p["in"] == "path" && p["name"] == name.as_str())
+                    })
+            })
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            push_parameters(&mut operation, missing);
+        }
+
+        let mut responses = Map::new();
+        for (status, reply) in &self.responses {
+            let mut response = match reply {
+                Reply::Output(output) => (output)(generator),
+                Reply::Description(description) => json!({ "description": description }),
+            };
+            if let Some(response) = response.as_object_mut() {
+                response.entry("description").or_insert_with(|| {
+                    json!(status.canonical_reason().unwrap_or_else(|| status.as_str()))
+                });
+            }
+            responses.insert(status.as_str().to_string(), response);
+        }
+        if !responses.is_empty() {
+            operation.insert("responses".to_string(), Value::Object(responses));
+        }
+
+        if self.deprecated {
+            operation.insert("deprecated".to_string(), json!(true));
+        }
+
+        Value::Object(operation)
+    }
+}
+
+impl fmt::Debug for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Operation")
+            .field("summary", &self.summary)
+            .field("description", &self.description)
+            .field("operation_id", &self.operation_id)
+            .field("tags", &self.tags)
+            .field("deprecated", &self.deprecated)
+            .field("hidden", &self.hidden)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The `OpenAPI` document builder.
+#[derive(Debug, Clone)]
+pub struct OpenApi {
+    info: Info,
+    operations: Vec<(String, Method, Operation)>,
+}
+
+impl OpenApi {
+    /// The version of the `OpenAPI` specification.
+    pub const VERSION: &'static str = "3.1.0";
+
+    /// Creates a new document builder.
+    #[must_use]
+    pub fn new(info: Info) -> Self {
+        Self {
+            info,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Adds an operation by the route path, e.g. `/users/:id`, and the HTTP verb.
+    #[must_use]
+    pub fn operation<S>(mut self, path: S, method: Method, operation: Operation) -> Self
+    where
+        S: AsRef<str>,
+    {
+        if !operation.hidden {
+            self.operations
+                .push((path.as_ref().to_string(), method, operation));
+        }
+        self
+    }
+
+    /// Generates the document.
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        let mut generator = schemars::generate::SchemaSettings::draft2020_12()
+            .with(|settings| {
+                settings.definitions_path = "/components/schemas".into();
+                settings.meta_schema = None;
+            })
+            .into_generator();
+
+        let mut paths = Map::new();
+        for (path, method, operation) in &self.operations {
+            let Some(method) = method_name(method) else {
+                continue;
+            };
+            let (path, params) = convert_path(path);
+            let operation = operation.to_value(&mut generator, &params);
+            if let Some(item) = paths
+                .entry(path)
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+            {
+                item.insert(method.to_string(), operation);
+            }
+        }
+
+        let mut info = Map::new();
+        info.insert("title".to_string(), json!(self.info.title));
+        if let Some(description) = &self.info.description {
+            info.insert("description".to_string(), json!(description));
+        }
+        info.insert("version".to_string(), json!(self.info.version));
+
+        let mut document = Map::new();
+        document.insert("openapi".to_string(), json!(Self::VERSION));
+        document.insert("info".to_string(), Value::Object(info));
+        document.insert("paths".to_string(), Value::Object(paths));
+
+        let schemas = generator.take_definitions(true);
+        if !schemas.is_empty() {
+            document.insert("components".to_string(), json!({ "schemas": schemas }));
+        }
+
+        Value::Object(document)
+    }
+}
+
+/// The HTTP verbs of the path item object.
+fn method_name(method: &Method) -> Option<&'static str> {
+    Some(match *method {
+        Method::GET => "get",
+        Method::PUT => "put",
+        Method::POST => "post",
+        Method::DELETE => "delete",
+        Method::OPTIONS => "options",
+        Method::HEAD => "head",
+        Method::PATCH => "patch",
+        Method::TRACE => "trace",
+        _ => return None,
+    })
+}
+
+/// Converts the route path to the `OpenAPI` path and collects the parameters,
+/// e.g. `users/:id` to `/users/{id}`, the unnamed wildcards are named as `*1`, `*2`, ...
+fn convert_path(path: &str) -> (String, Vec<String>) {
+    let mut result = String::with_capacity(path.len() + 1);
+    let mut params = Vec::new();
+    let mut chars = path.trim_start_matches('/').chars().peekable();
+    let mut wildcards = 0;
+
+    result.push('/');
+
+    while let Some(c) = chars.next() {
+        match c {
+            ':' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                chars.next_if(|c| matches!(c, '?' | '+' | '*'));
+                result.push('{');
+                result.push_str(&name);
+                result.push('}');
+                params.push(name);
+            }
+            '*' | '+' => {
+                wildcards += 1;
+                let name = format!("*{wildcards}");
+                result.push('{');
+                result.push_str(&name);
+                result.push('}');
+                params.push(name);
+            }
+            c => result.push(c),
+        }
+    }
+
+    (result, params)
+}
+
+fn push_parameters(operation: &mut Map<String, Value>, parameters: Vec<Value>) {
+    if let Some(items) = operation
+        .entry("parameters")
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+    {
+        items.extend(parameters);
+    }
+}
+
+/// Adds the properties of the schema as the parameters in the location, `query` or `path`.
+fn parameters(schema: &Schema, operation: &mut Map<String, Value>, at: &str) {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    push_parameters(
+        operation,
+        properties
+            .iter()
+            .map(|(name, schema)| {
+                let mut parameter = Map::new();
+                parameter.insert("name".to_string(), json!(name));
+                parameter.insert("in".to_string(), json!(at));
+                if let Some(description) = schema.get("description") {
+                    parameter.insert("description".to_string(), description.clone());
+                }
+                parameter.insert(
+                    "required".to_string(),
+                    json!(at == "path" || required.iter().any(|r| r == name)),
+                );
+                parameter.insert("schema".to_string(), schema.clone());
+                Value::Object(parameter)
+            })
+            .collect(),
+    );
+}
+
+/// Adds the path parameters, the fields of a struct are matched by the names, the items of a
+/// tuple by the positions, and a single value describes every parameter.
+fn path_parameters(schema: &Schema, operation: &mut Map<String, Value>, names: &[String]) {
+    if schema.get("properties").is_some() {
+        parameters(schema, operation, "path");
+        return;
+    }
+
+    let items = schema
+        .get("prefixItems")
+        .or_else(|| schema.get("items"))
+        .and_then(Value::as_array);
+
+    push_parameters(
+        operation,
+        names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| {
+                let schema = match items {
+                    Some(items) => items.get(i)?,
+                    None => schema.as_value(),
+                };
+                Some(json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": schema,
+                }))
+            })
+            .collect(),
+    );
+}
+
+/// Sets the request body with the content type.
+fn request_body<T>(generator: &mut SchemaGenerator, operation: &mut Map<String, Value>, mime: &str)
+where
+    T: JsonSchema,
+{
+    operation.insert(
+        "requestBody".to_string(),
+        json!({
+            "required": true,
+            "content": {
+                mime: { "schema": generator.subschema_for::<T>() },
+            },
+        }),
+    );
+}
+
+impl OperationInput for crate::Request {}
+
+impl OperationInput for crate::Body {}
+
+impl<T> OperationInput for Option<T>
+where
+    T: OperationInput,
+{
+    fn input(
+        generator: &mut SchemaGenerator,
+        operation: &mut Map<String, Value>,
+        path_params: &[String],
+    ) {
+        T::input(generator, operation, path_params);
+    }
+}
+
+impl<T, E> OperationInput for Result<T, E>
+where
+    T: OperationInput,
+{
+    fn input(
+        generator: &mut SchemaGenerator,
+        operation: &mut Map<String, Value>,
+        path_params: &[String],
+    ) {
+        T::input(generator, operation, path_params);
+    }
+}
+
+impl OperationInput for crate::types::Accept {}
+
+impl<T> OperationInput for crate::types::Header<T> {}
+
+#[cfg(feature = "state")]
+impl<T> OperationInput for crate::types::State<T> {}
+
+#[cfg(feature = "inject")]
+impl<T> OperationInput for crate::types::Inject<T> {}
+
+#[cfg(feature = "cookie")]
+impl OperationInput for crate::types::Cookies {}
+
+#[cfg(feature = "session")]
+impl OperationInput for crate::types::Session {}
+
+#[cfg(feature = "limits")]
+impl OperationInput for crate::types::Limits {}
+
+#[cfg(feature = "request-id")]
+impl OperationInput for crate::middleware::request_id::RequestId {}
+
+#[cfg(feature = "csrf")]
+impl OperationInput for crate::middleware::csrf::CsrfToken {}
+
+#[cfg(feature = "multipart")]
+impl OperationInput for crate::types::Multipart {}
+
+#[cfg(feature = "multipart-form")]
+impl<T> OperationInput for crate::types::MultipartForm<T> {}
+
+#[cfg(feature = "websocket")]
+impl OperationInput for crate::types::WebSocket {}
+
+#[cfg(feature = "protobuf")]
+impl<T> OperationInput for crate::types::Protobuf<T> {}
+
+#[cfg(feature = "validator")]
+impl<T> OperationInput for crate::types::Valid<T>
+where
+    T: OperationInput,
+{
+    fn input(
+        generator: &mut SchemaGenerator,
+        operation: &mut Map<String, Value>,
+        path_params: &[String],
+    ) {
+        T::input(generator, operation, path_params);
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> OperationInput for crate::types::Json<T>
+where
+    T: JsonSchema,
+{
+    fn input(generator: &mut SchemaGenerator, operation: &mut Map<String, Value>, _: &[String]) {
+        request_body::<T>(generator, operation, mime::APPLICATION_JSON.as_ref());
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> OperationOutput for crate::types::Json<T>
+where
+    T: JsonSchema,
+{
+    fn output(generator: &mut SchemaGenerator) -> Value {
+        json!({
+            "content": {
+                mime::APPLICATION_JSON.as_ref(): { "schema": generator.subschema_for::<T>() },
+            },
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> OperationInput for crate::types::JsonLines<T>
+where
+    T: JsonSchema,
+{
+    fn input(generator: &mut SchemaGenerator, operation: &mut Map<String, Value>, _: &[String]) {
+        request_body::<T>(generator, operation, "application/x-ndjson");
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T> OperationInput for crate::types::MsgPack<T>
+where
+    T: JsonSchema,
+{
+    fn input(generator: &mut SchemaGenerator, operation: &mut Map<String, Value>, _: &[String]) {
+        request_body::<T>(generator, operation, "application/msgpack");
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<T> OperationInput for crate::types::Cbor<T>
+where
+    T: JsonSchema,
+{
+    fn input(generator: &mut SchemaGenerator, operation: &mut Map<String, Value>, _: &[String]) {
+        request_body::<T>(generator, operation, "application/cbor");
+    }
+}
+
+#[cfg(feature = "form")]
+impl<T> OperationInput for crate::types::Form<T>
+where
+    T: JsonSchema,
+{
+    fn input(generator: &mut SchemaGenerator, operation: &mut Map<String, Value>, _: &[String]) {
+        request_body::<T>(
+            generator,
+            operation,
+            mime::APPLICATION_WWW_FORM_URLENCODED.as_ref(),
+        );
+    }
+}
+
+#[cfg(feature = "query")]
+impl<T> OperationInput for crate::types::Query<T>
+where
+    T: JsonSchema,
+{
+    fn input(generator: &mut SchemaGenerator, operation: &mut Map<String, Value>, _: &[String]) {
+        parameters(&T::json_schema(generator), operation, "query");
+    }
+}
+
+#[cfg(feature = "query")]
+impl<T> OperationInput for crate::types::NestedQuery<T>
+where
+    T: JsonSchema,
+{
+    fn input(generator: &mut SchemaGenerator, operation: &mut Map<String, Value>, _: &[String]) {
+        parameters(&T::json_schema(generator), operation, "query");
+    }
+}
+
+#[cfg(feature = "params")]
+impl<T> OperationInput for crate::types::Params<T>
+where
+    T: JsonSchema,
+{
+    fn input(
+        generator: &mut SchemaGenerator,
+        operation: &mut Map<String, Value>,
+        path_params: &[String],
+    ) {
+        path_parameters(&T::json_schema(generator), operation, path_params);
+    }
+}
+
+impl<T, E> OperationOutput for Result<T, E>
+where
+    T: OperationOutput,
+{
+    fn output(generator: &mut SchemaGenerator) -> Value {
+        T::output(generator)
+    }
+}
+
+impl OperationOutput for crate::Response {}
+
+impl OperationOutput for StatusCode {}
+
+impl OperationOutput for String {
+    fn output(_: &mut SchemaGenerator) -> Value {
+        json!({
+            "content": {
+                mime::TEXT_PLAIN_UTF_8.as_ref(): { "schema": { "type": "string" } },
+            },
+        })
+    }
+}
+
+impl OperationOutput for &'static str {
+    fn output(generator: &mut SchemaGenerator) -> Value {
+        String::output(generator)
+    }
+}
+
+impl OperationOutput for () {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        assert_eq!(convert_path(""), ("/".to_string(), vec![]));
+        assert_eq!(
+            convert_path("users/:user_id/posts/:id"),
+            (
+                "/users/{user_id}/posts/{id}".to_string(),
+                vec!["user_id".to_string(), "id".to_string()]
+            )
+        );
+        assert_eq!(
+            convert_path("/files/:name.:ext?"),
+            (
+                "/files/{name}.{ext}".to_string(),
+                vec!["name".to_string(), "ext".to_string()]
+            )
+        );
+        assert_eq!(
+            convert_path("static/*"),
+            ("/static/{*1}".to_string(), vec!["*1".to_string()])
+        );
+    }
+}
@@ -1,12 +1,11 @@
 use crate::{
     header,
     types::{PayloadError, RealIp},
-    Body, BodyState, Bytes, FromRequest, Future, Request, Result,
+    Body, BodyState, Bytes, FromRequest, FromRequestParts, Future, Request, Result,
 };
 use headers::HeaderMapExt;
 use http_body_util::{BodyExt, Collected};
 
-#[cfg(any(feature = "params", feature = "multipart"))]
 use std::sync::Arc;
 
 #[cfg(feature = "limits")]
@@ -14,7 +13,14 @@ use crate::types::Limits;
 #[cfg(feature = "limits")]
 use http_body_util::{LengthLimitError, Limited};
 
-#[cfg(any(feature = "form", feature = "json", feature = "multipart"))]
+#[cfg(any(
+    feature = "form",
+    feature = "json",
+    feature = "msgpack",
+    feature = "cbor",
+    feature = "protobuf",
+    feature = "multipart"
+))]
 use crate::types::Payload;
 
 #[cfg(feature = "form")]
@@ -23,6 +29,15 @@ use crate::types::Form;
 #[cfg(feature = "json")]
 use crate::types::Json;
 
+#[cfg(feature = "msgpack")]
+use crate::types::MsgPack;
+
+#[cfg(feature = "cbor")]
+use crate::types::Cbor;
+
+#[cfg(feature = "protobuf")]
+use crate::types::Protobuf;
+
 #[cfg(feature = "multipart")]
 use crate::types::Multipart;
 
@@ -78,6 +93,11 @@ pub trait RequestExt: private::Sealed + Sized {
     where
         T: FromRequest;
 
+    /// Extract the data from the parts of this request by the specified type.
+    fn extract_parts<T>(&self) -> impl Future<Output = Result<T, T::Error>> + Send
+    where
+        T: FromRequestParts;
+
     /// Get an incoming body.
     ///
     /// # Errors
@@ -113,6 +133,31 @@ pub trait RequestExt: private::Sealed + Sized {
     where
         T: serde::de::DeserializeOwned;
 
+    /// Return with a [`MessagePack`][spec] by the specified type representation of the request body.
+    ///
+    /// [spec]: <https://msgpack.org>
+    #[cfg(feature = "msgpack")]
+    fn msgpack<T>(&mut self) -> impl Future<Output = Result<T, PayloadError>> + Send
+    where
+        T: serde::de::DeserializeOwned;
+
+    /// Return with a [CBOR][rfc] by the specified type representation of the request body.
+    ///
+    /// [rfc]: <https://www.rfc-editor.org/rfc/rfc8949>
+    #[cfg(feature = "cbor")]
+    fn cbor<T>(&mut self) -> impl Future<Output = Result<T, PayloadError>> + Send
+    where
+        T: serde::de::DeserializeOwned;
+
+    /// Return with a [Protocol Buffers][spec] message by the specified type representation
+    /// of the request body.
+    ///
+    /// [spec]: <https://protobuf.dev>
+    #[cfg(feature = "protobuf")]
+    fn protobuf<T>(&mut self) -> impl Future<Output = Result<T, PayloadError>> + Send
+    where
+        T: prost::Message + Default;
+
     /// Return with a `multipart/form-data` [FormData][mdn] by the specified type
     /// representation of the request body.
     ///
@@ -239,6 +284,13 @@ impl RequestExt for Request {
         T::extract(self).await
     }
 
+    async fn extract_parts<T>(&self) -> Result<T, T::Error>
+    where
+        T: FromRequestParts,
+    {
+        T::extract_parts(self).await
+    }
+
     fn incoming(&mut self) -> Result<Body, PayloadError> {
         if let Some(state) = self.extensions().get::<BodyState>() {
             match state {
@@ -299,6 +351,36 @@ impl RequestExt for Request {
         serde_json::from_slice(&bytes).map_err(PayloadError::Json)
     }
 
+    #[cfg(feature = "msgpack")]
+    async fn msgpack<T>(&mut self) -> Result<T, PayloadError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        <MsgPack as Payload>::check_type(self.content_type())?;
+        let bytes = self.bytes().await?;
+        rmp_serde::from_slice(&bytes).map_err(PayloadError::MsgPack)
+    }
+
+    #[cfg(feature = "cbor")]
+    async fn cbor<T>(&mut self) -> Result<T, PayloadError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        <Cbor as Payload>::check_type(self.content_type())?;
+        let bytes = self.bytes().await?;
+        ciborium::from_reader(bytes::Buf::reader(bytes)).map_err(PayloadError::Cbor)
+    }
+
+    #[cfg(feature = "protobuf")]
+    async fn protobuf<T>(&mut self) -> Result<T, PayloadError>
+    where
+        T: prost::Message + Default,
+    {
+        <Protobuf as Payload>::check_type(self.content_type())?;
+        let bytes = self.bytes().await?;
+        T::decode(bytes).map_err(PayloadError::Protobuf)
+    }
+
     #[cfg(feature = "multipart")]
     async fn multipart(&mut self) -> Result<Multipart, PayloadError> {
         let m = <Multipart as Payload>::check_type(self.content_type())?;
@@ -366,7 +448,11 @@ impl RequestExt for Request {
     }
 
     fn remote_addr(&self) -> Option<&std::net::SocketAddr> {
-        self.extensions().get()
+        // The server shares the address of the connection with its requests.
+        self.extensions()
+            .get::<Arc<std::net::SocketAddr>>()
+            .map(AsRef::as_ref)
+            .or_else(|| self.extensions().get())
     }
 
     #[cfg(feature = "params")]
@@ -416,6 +502,32 @@ pub trait RequestLimitsExt: private::Sealed + Sized {
     where
         T: serde::de::DeserializeOwned;
 
+    /// Return with a limited [`MessagePack`][spec] by the specified type representation of the
+    /// request body.
+    ///
+    /// [spec]: <https://msgpack.org>
+    #[cfg(feature = "msgpack")]
+    fn msgpack_with_limit<T>(&mut self) -> impl Future<Output = Result<T, PayloadError>> + Send
+    where
+        T: serde::de::DeserializeOwned;
+
+    /// Return with a limited [CBOR][rfc] by the specified type representation of the request body.
+    ///
+    /// [rfc]: <https://www.rfc-editor.org/rfc/rfc8949>
+    #[cfg(feature = "cbor")]
+    fn cbor_with_limit<T>(&mut self) -> impl Future<Output = Result<T, PayloadError>> + Send
+    where
+        T: serde::de::DeserializeOwned;
+
+    /// Return with a limited [Protocol Buffers][spec] message by the specified type
+    /// representation of the request body.
+    ///
+    /// [spec]: <https://protobuf.dev>
+    #[cfg(feature = "protobuf")]
+    fn protobuf_with_limit<T>(&mut self) -> impl Future<Output = Result<T, PayloadError>> + Send
+    where
+        T: prost::Message + Default;
+
     /// Return with a limited `multipart/form-data` [FormData][mdn] by the specified type
     /// representation of the request body.
     ///
@@ -482,6 +594,39 @@ impl RequestLimitsExt for Request {
         serde_json::from_slice(&bytes).map_err(PayloadError::Json)
     }
 
+    #[cfg(feature = "msgpack")]
+    async fn msgpack_with_limit<T>(&mut self) -> Result<T, PayloadError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let limit = self.limits().get(<MsgPack as Payload>::NAME);
+        <MsgPack as Payload>::check_header(self.content_type(), self.content_length(), limit)?;
+        let bytes = self.bytes_with(limit, <MsgPack as Payload>::LIMIT).await?;
+        rmp_serde::from_slice(&bytes).map_err(PayloadError::MsgPack)
+    }
+
+    #[cfg(feature = "cbor")]
+    async fn cbor_with_limit<T>(&mut self) -> Result<T, PayloadError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let limit = self.limits().get(<Cbor as Payload>::NAME);
+        <Cbor as Payload>::check_header(self.content_type(), self.content_length(), limit)?;
+        let bytes = self.bytes_with(limit, <Cbor as Payload>::LIMIT).await?;
+        ciborium::from_reader(bytes::Buf::reader(bytes)).map_err(PayloadError::Cbor)
+    }
+
+    #[cfg(feature = "protobuf")]
+    async fn protobuf_with_limit<T>(&mut self) -> Result<T, PayloadError>
+    where
+        T: prost::Message + Default,
+    {
+        let limit = self.limits().get(<Protobuf as Payload>::NAME);
+        <Protobuf as Payload>::check_header(self.content_type(), self.content_length(), limit)?;
+        let bytes = self.bytes_with(limit, <Protobuf as Payload>::LIMIT).await?;
+        T::decode(bytes).map_err(PayloadError::Protobuf)
+    }
+
     #[cfg(feature = "multipart")]
     async fn multipart_with_limit(&mut self) -> Result<Multipart, PayloadError> {
         let limit = self.limits().get(<Multipart as Payload>::NAME);
@@ -82,6 +82,48 @@ pub trait ResponseExt: private::Sealed + Sized {
             .map_err(crate::types::PayloadError::Json)
     }
 
+    /// The response with `application/msgpack` media type, the structs are serialized as maps.
+    ///
+    /// # Errors
+    ///
+    /// Throws an error if serialization fails.
+    #[cfg(feature = "msgpack")]
+    fn msgpack<T>(body: T) -> Result<Response, crate::types::PayloadError>
+    where
+        T: serde::Serialize,
+    {
+        rmp_serde::to_vec_named(&body)
+            .map(|buf| Self::with(Full::from(buf), "application/msgpack"))
+            .map_err(crate::types::PayloadError::MsgPackEncode)
+    }
+
+    /// The response with `application/cbor` media type.
+    ///
+    /// # Errors
+    ///
+    /// Throws an error if serialization fails.
+    #[cfg(feature = "cbor")]
+    fn cbor<T>(body: T) -> Result<Response, crate::types::PayloadError>
+    where
+        T: serde::Serialize,
+    {
+        use bytes::{BufMut, BytesMut};
+
+        let mut buf = BytesMut::with_capacity(128).writer();
+        ciborium::into_writer(&body, &mut buf)
+            .map(|()| Self::with(Full::new(buf.into_inner().freeze()), "application/cbor"))
+            .map_err(crate::types::PayloadError::CborEncode)
+    }
+
+    /// The response with `application/x-protobuf` media type.
+    #[cfg(feature = "protobuf")]
+    fn protobuf<T>(body: &T) -> Response
+    where
+        T: prost::Message,
+    {
+        Self::with(Full::from(body.encode_to_vec()), "application/x-protobuf")
+    }
+
     /// Responds to a stream.
     fn stream<S, D, E>(stream: S) -> Response
     where
@@ -10,7 +10,12 @@ pub use self::cookie::{Cookie, CookieJar, Cookies, CookiesError, SameSite};
 #[cfg(feature = "state")]
 mod state;
 #[cfg(feature = "state")]
-pub use state::{State, StateError};
+pub use state::{FromRef, State, StateCell, StateError, StateHandler};
+
+#[cfg(feature = "inject")]
+mod inject;
+#[cfg(feature = "inject")]
+pub use inject::{Factory, Inject, InjectError, Providers, ProvidersMiddleware};
 
 #[cfg(feature = "form")]
 mod form;
@@ -22,6 +27,26 @@ mod json;
 #[cfg(feature = "json")]
 pub use json::Json;
 
+#[cfg(feature = "json")]
+mod json_lines;
+#[cfg(feature = "json")]
+pub use json_lines::JsonLines;
+
+#[cfg(feature = "msgpack")]
+mod msgpack;
+#[cfg(feature = "msgpack")]
+pub use msgpack::MsgPack;
+
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "cbor")]
+pub use cbor::Cbor;
+
+#[cfg(feature = "protobuf")]
+mod protobuf;
+#[cfg(feature = "protobuf")]
+pub use protobuf::Protobuf;
+
 #[cfg(feature = "limits")]
 mod limits;
 #[cfg(feature = "limits")]
@@ -32,6 +57,13 @@ mod multipart;
 #[cfg(feature = "multipart")]
 pub use multipart::{Multipart, MultipartError, MultipartLimits};
 
+#[cfg(feature = "multipart-form")]
+mod multipart_form;
+#[cfg(feature = "multipart-form")]
+pub use multipart_form::{
+    FileRule, MultipartFiles, MultipartForm, MultipartFormConfig, MultipartFormError, TempFile,
+};
+
 #[cfg(feature = "params")]
 mod params;
 #[cfg(feature = "params")]
@@ -42,7 +74,7 @@ pub use params::{Params, ParamsError};
 #[cfg(feature = "query")]
 mod query;
 #[cfg(feature = "query")]
-pub use query::Query;
+pub use query::{ArrayFormat, NestedQuery, Query, QueryConfig, QueryError};
 
 #[cfg(feature = "session")]
 mod session;
@@ -59,16 +91,47 @@ mod websocket;
 #[cfg(feature = "websocket")]
 pub use websocket::{Message, WebSocket, WebSocketConfig, WebSocketError, WebSocketStream};
 
+#[cfg(feature = "problem")]
+mod problem;
+#[cfg(feature = "problem")]
+pub use problem::Problem;
+
+#[cfg(feature = "validator")]
+mod valid;
+#[cfg(feature = "validator")]
+pub use valid::{Valid, ValidError};
+
 #[cfg(feature = "params")]
 mod route_info;
 #[cfg(feature = "params")]
 pub use route_info::RouteInfo;
 
+#[cfg(feature = "params")]
+mod typed_path;
+#[cfg(feature = "params")]
+#[doc(hidden)]
+pub use typed_path::encode_path_param;
+#[cfg(feature = "params")]
+pub use typed_path::TypedPath;
+
+#[cfg(feature = "json")]
+mod negotiate;
+#[cfg(feature = "json")]
+pub use negotiate::Negotiate;
+
+mod accept;
+pub use accept::{Accept, MediaRange};
+
 mod header;
-pub use header::{Header, HeaderError};
+pub use header::{Header, HeaderError, Headers, TypedHeaders};
 
 mod payload;
 pub use payload::{Payload, PayloadError};
 
 mod realip;
 pub use realip::RealIp;
+
+#[cfg(any(feature = "ip-filter", feature = "logger"))]
+mod cidr;
+#[cfg(any(feature = "ip-filter", feature = "logger"))]
+pub use cidr::{Cidr, CidrError};
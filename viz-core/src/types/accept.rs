@@ -0,0 +1,170 @@
+//! Represents an `Accept` header extractor.
+
+use std::{convert::Infallible, ops::Deref};
+
+use crate::{
+    header::{HeaderMap, ACCEPT},
+    FromRequest, FromRequestParts, Request, Result,
+};
+
+/// A media range with its quality value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaRange {
+    /// The media range, e.g. `text/html`, `text/*` or `*/*`.
+    pub mime: mime::Mime,
+    /// The quality value, between `0.0` and `1.0`.
+    pub q: f32,
+}
+
+impl MediaRange {
+    /// Checks the media type is matched by the range or not.
+    #[must_use]
+    pub fn matches(&self, m: &mime::Mime) -> bool {
+        (self.mime.type_() == mime::STAR || self.mime.type_() == m.type_())
+            && (self.mime.subtype() == mime::STAR || self.mime.subtype() == m.subtype())
+    }
+
+    /// The specificity of the range, `*/*` is the lowest.
+    fn specificity(&self) -> u8 {
+        u8::from(self.mime.type_() != mime::STAR) + u8::from(self.mime.subtype() != mime::STAR)
+    }
+}
+
+/// Extracts the media ranges from the `Accept` header, sorted by the quality values.
+///
+/// An empty `Accept` means that all media types are acceptable.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Accept(Vec<MediaRange>);
+
+impl Accept {
+    /// Parses the `Accept` headers, the invalid media ranges are skipped.
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut ranges = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|range| {
+                let mime = range.trim().parse::<mime::Mime>().ok()?;
+                let q = mime
+                    .get_param("q")
+                    .and_then(|q| q.as_str().parse::<f32>().ok())
+                    .map_or(1.0, |q| q.clamp(0.0, 1.0));
+                Some(MediaRange { mime, q })
+            })
+            .collect::<Vec<_>>();
+
+        ranges.sort_by(|a, b| {
+            b.q.total_cmp(&a.q)
+                .then_with(|| b.specificity().cmp(&a.specificity()))
+        });
+
+        Self(ranges)
+    }
+
+    /// Gets the quality value of the media type by the most specific range.
+    #[must_use]
+    pub fn quality(&self, m: &mime::Mime) -> f32 {
+        if self.0.is_empty() {
+            return 1.0;
+        }
+        self.0
+            .iter()
+            .filter(|range| range.matches(m))
+            .max_by_key(|range| range.specificity())
+            .map_or(0.0, |range| range.q)
+    }
+
+    /// Picks the acceptable media type with the highest quality, the former is preferred
+    /// when the qualities are equal.
+    #[must_use]
+    pub fn negotiate<'a>(&self, available: &'a [mime::Mime]) -> Option<&'a mime::Mime> {
+        available
+            .iter()
+            .map(|m| (m, self.quality(m)))
+            .filter(|(_, q)| *q > 0.0)
+            .fold(
+                None,
+                |best: Option<(&mime::Mime, f32)>, (m, q)| match best {
+                    Some((_, best_q)) if best_q >= q => best,
+                    _ => Some((m, q)),
+                },
+            )
+            .map(|(m, _)| m)
+    }
+}
+
+impl Deref for Accept {
+    type Target = [MediaRange];
+
+    fn deref(&self) -> &[MediaRange] {
+        &self.0
+    }
+}
+
+impl FromRequestParts for Accept {
+    type Error = Infallible;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        Ok(Self::from_headers(req.headers()))
+    }
+}
+
+impl FromRequest for Accept {
+    type Error = Infallible;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        Self::extract_parts(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderValue;
+
+    fn accept(value: &'static str) -> Accept {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(value));
+        Accept::from_headers(&headers)
+    }
+
+    #[test]
+    fn parse() {
+        let a = accept("text/*;q=0.3, text/html;q=0.7, text/html;level=1, */*;q=0.5, invalid");
+        assert_eq!(a.len(), 4);
+        assert_eq!(a[0].mime.essence_str(), "text/html");
+        assert_eq!(a[1].mime.essence_str(), "text/html");
+        assert!((a[1].q - 0.7).abs() < f32::EPSILON);
+        assert_eq!(a[2].mime.essence_str(), "*/*");
+        assert_eq!(a[3].mime.essence_str(), "text/*");
+
+        assert!((a.quality(&mime::TEXT_PLAIN) - 0.3).abs() < f32::EPSILON);
+        assert!((a.quality(&mime::IMAGE_PNG) - 0.5).abs() < f32::EPSILON);
+        assert!((Accept::default().quality(&mime::IMAGE_PNG) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn negotiate() {
+        let available = [mime::APPLICATION_JSON, mime::TEXT_HTML, mime::TEXT_PLAIN];
+
+        assert_eq!(
+            accept("text/html,application/xhtml+xml,*/*;q=0.8").negotiate(&available),
+            Some(&mime::TEXT_HTML)
+        );
+        assert_eq!(
+            accept("*/*").negotiate(&available),
+            Some(&mime::APPLICATION_JSON)
+        );
+        assert_eq!(
+            accept("text/*, application/json;q=0.5").negotiate(&available),
+            Some(&mime::TEXT_HTML)
+        );
+        assert_eq!(accept("image/png").negotiate(&available), None);
+        assert_eq!(
+            Accept::default().negotiate(&available),
+            Some(&mime::APPLICATION_JSON)
+        );
+    }
+}
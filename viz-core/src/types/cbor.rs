@@ -0,0 +1,116 @@
+//! Represents a CBOR extractor or responder.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{FromRequest, IntoResponse, Request, RequestExt, Response, ResponseExt, Result};
+
+#[cfg(feature = "limits")]
+use crate::RequestLimitsExt;
+
+use super::{Payload, PayloadError};
+
+/// The media type of CBOR.
+const MIME: &str = "application/cbor";
+
+/// Extracts CBOR data from the body of a request, or responds a CBOR data to response.
+pub struct Cbor<T = ()>(pub T);
+
+impl<T> Cbor<T> {
+    /// Create new `Cbor` instance.
+    #[inline]
+    pub fn new(data: T) -> Self {
+        Cbor(data)
+    }
+
+    /// Consumes the Cbor, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Clone for Cbor<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Cbor(self.0.clone())
+    }
+}
+
+impl<T> AsRef<T> for Cbor<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Deref for Cbor<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Cbor<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for Cbor<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T> Payload for Cbor<T> {
+    const NAME: &'static str = "cbor";
+
+    // 1MB
+    const LIMIT: u64 = 1024 * 1024;
+
+    fn detect(m: &mime::Mime) -> bool {
+        m.type_() == mime::APPLICATION
+            && (m.subtype() == "cbor" || m.suffix().is_some_and(|s| s == "cbor"))
+    }
+
+    fn mime() -> mime::Mime {
+        MIME.parse().expect("valid media type")
+    }
+}
+
+impl<T> FromRequest for Cbor<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Error = PayloadError;
+
+    #[inline]
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        #[cfg(feature = "limits")]
+        if req.extensions().get::<super::Limits>().is_some() {
+            return req.cbor_with_limit().await.map(Self);
+        }
+        req.cbor().await.map(Self)
+    }
+}
+
+/// Responds with CBOR Data.
+impl<T> IntoResponse for Cbor<T>
+where
+    T: serde::Serialize,
+{
+    fn into_response(self) -> Response {
+        match Response::cbor(self.0) {
+            Ok(res) => res,
+            Err(err) => err.into_response(),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! CIDR ranges of the IP addresses.
+
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use crate::{Result, ThisError};
+
+/// An IPv4 or IPv6 network range, e.g. `10.0.0.0/8` or `fd00::/8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Creates a new CIDR range, the host bits of the address are cleared.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`CidrError::Prefix`] if the prefix length is too long.
+    pub fn new(addr: IpAddr, prefix: u8) -> Result<Self, CidrError> {
+        let addr = addr.to_canonical();
+        let max = max_prefix(&addr);
+        if prefix > max {
+            return Err(CidrError::Prefix(prefix));
+        }
+        Ok(Self {
+            addr: mask(&addr, prefix),
+            prefix,
+        })
+    }
+
+    /// Gets the network address.
+    #[must_use]
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Gets the prefix length.
+    #[must_use]
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Checks the IP address is in the range or not.
+    #[must_use]
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        ip.is_ipv4() == self.addr.is_ipv4() && mask(&ip, self.prefix) == self.addr
+    }
+}
+
+impl From<IpAddr> for Cidr {
+    fn from(addr: IpAddr) -> Self {
+        let addr = addr.to_canonical();
+        Self {
+            prefix: max_prefix(&addr),
+            addr,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = CidrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (
+                addr,
+                Some(
+                    prefix
+                        .parse()
+                        .map_err(|_| CidrError::Invalid(s.to_string()))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        let addr = addr
+            .parse::<IpAddr>()
+            .map_err(|_| CidrError::Invalid(s.to_string()))?;
+        match prefix {
+            Some(prefix) => Self::new(addr, prefix),
+            None => Ok(Self::from(addr)),
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// CIDR parsing error.
+#[derive(Debug, ThisError)]
+pub enum CidrError {
+    /// Invalid CIDR notation.
+    #[error("invalid CIDR `{0}`")]
+    Invalid(String),
+    /// The prefix length is too long.
+    #[error("invalid prefix length `{0}`")]
+    Prefix(u8),
+}
+
+fn max_prefix(addr: &IpAddr) -> u8 {
+    if addr.is_ipv4() {
+        32
+    } else {
+        128
+    }
+}
+
+fn mask(addr: &IpAddr, prefix: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(addr) => {
+            let bits = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(u32::from(*addr) & bits))
+        }
+        IpAddr::V6(addr) => {
+            let bits = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(u128::from(*addr) & bits))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cidr() {
+        let cidr: Cidr = "10.1.2.3/8".parse().unwrap();
+        assert_eq!(cidr.to_string(), "10.0.0.0/8");
+        assert!(cidr.contains(&"10.255.0.1".parse().unwrap()));
+        assert!(cidr.contains(&"::ffff:10.0.0.1".parse().unwrap()));
+        assert!(!cidr.contains(&"11.0.0.1".parse().unwrap()));
+        assert!(!cidr.contains(&"::1".parse().unwrap()));
+
+        let cidr: Cidr = "fd00::/8".parse().unwrap();
+        assert!(cidr.contains(&"fd12:3456::1".parse().unwrap()));
+        assert!(!cidr.contains(&"fe80::1".parse().unwrap()));
+
+        let cidr: Cidr = "0.0.0.0/0".parse().unwrap();
+        assert!(cidr.contains(&"8.8.8.8".parse().unwrap()));
+
+        let cidr: Cidr = "127.0.0.1".parse().unwrap();
+        assert_eq!(cidr.prefix(), 32);
+
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("10.0.0/8".parse::<Cidr>().is_err());
+    }
+}
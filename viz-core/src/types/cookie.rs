@@ -6,7 +6,8 @@ use std::{
 };
 
 use crate::{
-    Error, FromRequest, IntoResponse, Request, RequestExt, Response, StatusCode, ThisError,
+    Error, FromRequest, FromRequestParts, IntoResponse, Request, RequestExt, Response, StatusCode,
+    ThisError,
 };
 
 pub use ::cookie::{Cookie, CookieJar, SameSite};
@@ -192,11 +193,19 @@ impl Cookies {
     }
 }
 
+impl FromRequestParts for Cookies {
+    type Error = CookiesError;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        req.cookies()
+    }
+}
+
 impl FromRequest for Cookies {
     type Error = CookiesError;
 
     async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
-        req.cookies()
+        Self::extract_parts(req).await
     }
 }
 
@@ -8,7 +8,8 @@ use std::{
 use crate::{
     header,
     headers::{self, HeaderMapExt},
-    Error, FromRequest, IntoResponse, Request, Response, Result, StatusCode, ThisError,
+    Error, FromRequest, FromRequestParts, IntoResponse, Request, Response, Result, StatusCode,
+    ThisError,
 };
 
 /// Extracts a header from the headers of a request.
@@ -66,13 +67,13 @@ where
     }
 }
 
-impl<T> FromRequest for Header<T>
+impl<T> FromRequestParts for Header<T>
 where
     T: headers::Header,
 {
     type Error = HeaderError;
 
-    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
         req.headers()
             .typed_try_get::<T>()
             .map_err(|_| HeaderError::InvalidName(T::name()))
@@ -81,6 +82,57 @@ where
     }
 }
 
+impl<T> FromRequest for Header<T>
+where
+    T: headers::Header,
+{
+    type Error = HeaderError;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        Self::extract_parts(req).await
+    }
+}
+
+/// Extracts the typed headers from the headers of a request, e.g.
+/// `Headers<(ContentType, UserAgent)>`.
+#[derive(Debug, Clone)]
+pub struct Headers<T>(pub T);
+
+impl<T> Headers<T> {
+    /// Consumes the Headers, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Headers<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Headers<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// Responds the inner responder with the typed headers, which are inserted by the
+/// [`HeaderMapExt::typed_insert`], e.g. `TypedHeaders((ETag, CacheControl), body)`.
+#[derive(Debug, Clone)]
+pub struct TypedHeaders<H, T = ()>(pub H, pub T);
+
+impl<H, T> TypedHeaders<H, T> {
+    /// Create new `TypedHeaders` instance.
+    #[inline]
+    pub fn new(headers: H, t: T) -> Self {
+        Self(headers, t)
+    }
+}
+
 /// Rejects with an error when header extraction fails.
 #[derive(Debug, ThisError)]
 pub enum HeaderError {
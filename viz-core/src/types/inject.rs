@@ -0,0 +1,416 @@
+//! Represents a request-scoped dependency extractor and the providers of the dependencies.
+
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::{HashMap, HashSet},
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use futures_util::{future::BoxFuture, lock::Mutex as AsyncMutex};
+
+use crate::{
+    Body, Error, FromRequest, FromRequestParts, Future, Handler, IntoResponse, Request, Response,
+    Result, StatusCode, ThisError, Transform,
+};
+
+/// Extracts the dependency `T`, which is provided by the factory registered on the router.
+///
+/// The dependency is resolved lazily at the first extraction, then it is cached in the request,
+/// so the handler and the other factories get the same one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Inject<T>(pub T);
+
+impl<T> Inject<T> {
+    /// Consumes the `Inject`, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Inject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Inject<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> FromRequestParts for Inject<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Error = Error;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        let scope = req
+            .extensions()
+            .get::<Arc<Scope>>()
+            .ok_or_else(InjectError::new::<T>)?;
+        scope.resolve::<T>(req).await.map(Self)
+    }
+}
+
+impl<T> FromRequest for Inject<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Error = Error;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        Self::extract_parts(req).await
+    }
+}
+
+/// The factory of a dependency, it's an async function which takes the parts extractors, e.g.
+/// `async fn current_user(session: Session, Inject(db): Inject<Db>) -> Result<User>`.
+pub trait Factory<E>: Send + Sync + 'static {
+    /// The dependency.
+    type Output;
+
+    /// Provides the dependency from the parts of the HTTP [`Request`].
+    #[must_use]
+    fn provide(&self, req: &Request) -> impl Future<Output = Result<Self::Output>> + Send;
+}
+
+type Finalizer<T> =
+    Box<dyn Fn(T, &Result<Response>) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// An object-safe provider of the dependency.
+trait Provider: Send + Sync {
+    fn resolve<'a>(&'a self, req: &'a Request)
+        -> BoxFuture<'a, Result<Arc<dyn Any + Send + Sync>>>;
+
+    fn finalize(
+        &self,
+        value: &(dyn Any + Send + Sync),
+        result: &Result<Response>,
+    ) -> Option<BoxFuture<'static, Result<()>>>;
+}
+
+struct FactoryProvider<F, E, T> {
+    factory: F,
+    finalizer: Option<Finalizer<T>>,
+    _marker: PhantomData<fn(E)>,
+}
+
+impl<F, E, T> Provider for FactoryProvider<F, E, T>
+where
+    F: Factory<E, Output = T>,
+    T: Clone + Send + Sync + 'static,
+    E: 'static,
+{
+    fn resolve<'a>(
+        &'a self,
+        req: &'a Request,
+    ) -> BoxFuture<'a, Result<Arc<dyn Any + Send + Sync>>> {
+        Box::pin(async move {
+            let value = self.factory.provide(req).await?;
+            Ok(Arc::new(value) as Arc<dyn Any + Send + Sync>)
+        })
+    }
+
+    fn finalize(
+        &self,
+        value: &(dyn Any + Send + Sync),
+        result: &Result<Response>,
+    ) -> Option<BoxFuture<'static, Result<()>>> {
+        let finalizer = self.finalizer.as_ref()?;
+        let value = value.downcast_ref::<T>()?;
+        Some(finalizer(value.clone(), result))
+    }
+}
+
+/// The registry of the factories, it's a middleware which resolves the dependencies for the
+/// [`Inject`] extractors and runs the finalizers after the handler.
+#[derive(Clone, Default)]
+pub struct Providers(HashMap<TypeId, (&'static str, Arc<dyn Provider>)>);
+
+impl Providers {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the factory of a dependency, which is resolved by its output type.
+    #[must_use]
+    pub fn provide<F, E>(self, factory: F) -> Self
+    where
+        F: Factory<E>,
+        F::Output: Clone + Send + Sync + 'static,
+        E: 'static,
+    {
+        self.insert(FactoryProvider {
+            factory,
+            finalizer: None,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Registers the factory of a dependency with a finalizer, which sees the result of the
+    /// handler, e.g. commits the transaction on success and rolls back it on error.
+    ///
+    /// An error of the finalizer replaces the successful result of the handler.
+    #[must_use]
+    pub fn provide_with<F, E, Fin, Fut>(self, factory: F, finalizer: Fin) -> Self
+    where
+        F: Factory<E>,
+        F::Output: Clone + Send + Sync + 'static,
+        E: 'static,
+        Fin: Fn(F::Output, &Result<Response>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.insert(FactoryProvider {
+            factory,
+            finalizer: Some(Box::new(move |value, result| {
+                Box::pin(finalizer(value, result))
+            })),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Merges the other registry, the factories of self are kept.
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        for (id, provider) in other.0 {
+            self.0.entry(id).or_insert(provider);
+        }
+        self
+    }
+
+    fn insert<F, E, T>(mut self, provider: FactoryProvider<F, E, T>) -> Self
+    where
+        F: Factory<E, Output = T>,
+        T: Clone + Send + Sync + 'static,
+        E: 'static,
+    {
+        self.0
+            .insert(TypeId::of::<T>(), (type_name::<T>(), Arc::new(provider)));
+        self
+    }
+}
+
+impl fmt::Debug for Providers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.0.values().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+impl<H> Transform<H> for Providers
+where
+    H: Clone,
+{
+    type Output = ProvidersMiddleware<H>;
+
+    fn transform(&self, h: H) -> Self::Output {
+        ProvidersMiddleware {
+            h,
+            providers: Arc::new(self.clone()),
+        }
+    }
+}
+
+/// A middleware which creates the scope of the dependencies for each request.
+#[derive(Debug, Clone)]
+pub struct ProvidersMiddleware<H> {
+    h: H,
+    providers: Arc<Providers>,
+}
+
+#[crate::async_trait]
+impl<H, O> Handler<Request> for ProvidersMiddleware<H>
+where
+    H: Handler<Request, Output = Result<O>>,
+    O: IntoResponse,
+{
+    type Output = Result<Response>;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        let scope = Arc::new(Scope {
+            providers: self.providers.clone(),
+            parent: req.extensions().get::<Arc<Scope>>().cloned(),
+            cells: Mutex::default(),
+            resolved: Mutex::default(),
+            waits: Mutex::default(),
+        });
+        req.extensions_mut().insert(scope.clone());
+
+        let result = self.h.call(req).await.map(IntoResponse::into_response);
+        scope.finalize(result).await
+    }
+}
+
+type Cell = Arc<AsyncMutex<Option<Arc<dyn Any + Send + Sync>>>>;
+
+/// Marks the request passed to the factory with the type it resolves.
+#[derive(Clone, Copy)]
+struct Resolving(TypeId);
+
+/// The dependencies of a request, the nested scope falls back to the parent one.
+struct Scope {
+    providers: Arc<Providers>,
+    parent: Option<Arc<Scope>>,
+    cells: Mutex<HashMap<TypeId, Cell>>,
+    resolved: Mutex<Vec<TypeId>>,
+    /// The types which the factories being resolved wait for.
+    waits: Mutex<HashMap<TypeId, Vec<TypeId>>>,
+}
+
+impl Scope {
+    async fn resolve<T>(&self, req: &Request) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let id = TypeId::of::<T>();
+
+        // A factory which waits for a dependency which waits for it, even on another extraction,
+        // would wait for its own cell forever.
+        if let Some(Resolving(by)) = req.extensions().get::<Resolving>() {
+            if !self.wait(*by, id) {
+                return Err(InjectError::Cycle(type_name::<T>()).into());
+            }
+        }
+
+        // The dependency is resolved and cached in the scope which provides it.
+        let mut scope = self;
+        let provider = loop {
+            if let Some((_, provider)) = scope.providers.0.get(&id) {
+                break provider;
+            }
+            scope = scope.parent.as_deref().ok_or_else(InjectError::new::<T>)?;
+        };
+
+        let cell = scope
+            .cells
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(id)
+            .or_default()
+            .clone();
+
+        // The same dependency is only resolved once, even if it's extracted concurrently.
+        let mut value = cell.lock().await;
+        if value.is_none() {
+            let child = Self::child(req, id);
+            let resolved = provider.resolve(&child).await;
+            self.waits
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&id);
+            *value = Some(resolved?);
+            scope
+                .resolved
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(id);
+        }
+
+        value
+            .as_ref()
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+            .ok_or_else(|| InjectError::new::<T>().into_error())
+    }
+
+    /// Records that the factory of `by` waits for `id`, unless `id` already waits for `by`.
+    fn wait(&self, by: TypeId, id: TypeId) -> bool {
+        let mut waits = self.waits.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut seen = HashSet::new();
+        let mut stack = vec![id];
+        while let Some(next) = stack.pop() {
+            if next == by {
+                return false;
+            }
+            if seen.insert(next) {
+                stack.extend(waits.get(&next).into_iter().flatten());
+            }
+        }
+        waits.entry(by).or_default().push(id);
+        true
+    }
+
+    /// Copies the parts of the request for the factory of `id`, the body is left empty.
+    fn child(req: &Request, id: TypeId) -> Request {
+        let mut child = Request::new(Body::Empty);
+        child.method_mut().clone_from(req.method());
+        child.uri_mut().clone_from(req.uri());
+        *child.version_mut() = req.version();
+        child.headers_mut().clone_from(req.headers());
+        child.extensions_mut().clone_from(req.extensions());
+        child.extensions_mut().insert(Resolving(id));
+        child
+    }
+
+    /// Runs the finalizers in the reverse order of the resolution.
+    async fn finalize(&self, mut result: Result<Response>) -> Result<Response> {
+        let resolved =
+            std::mem::take(&mut *self.resolved.lock().unwrap_or_else(PoisonError::into_inner));
+        for id in resolved.into_iter().rev() {
+            let Some((_, provider)) = self.providers.0.get(&id) else {
+                continue;
+            };
+            let cell = self
+                .cells
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(&id)
+                .cloned();
+            let Some(cell) = cell else {
+                continue;
+            };
+            let value = cell.lock().await.clone();
+            let Some(fut) = value.and_then(|value| provider.finalize(&*value, &result)) else {
+                continue;
+            };
+            if let Err(e) = fut.await {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// An [`Inject`] error.
+#[derive(ThisError, Debug)]
+pub enum InjectError {
+    /// The type is not provided.
+    #[error("missing provider of `{0}`")]
+    Missing(&'static str),
+    /// The type is requested again while it is being resolved.
+    #[error("cyclic dependency of `{0}`")]
+    Cycle(&'static str),
+}
+
+impl InjectError {
+    /// Creates an `Inject` Error of the missing provider.
+    #[must_use]
+    pub fn new<T>() -> Self {
+        InjectError::Missing(type_name::<T>())
+    }
+}
+
+impl IntoResponse for InjectError {
+    fn into_response(self) -> Response {
+        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
+}
+
+impl From<InjectError> for Error {
+    fn from(e: InjectError) -> Self {
+        e.into_error()
+    }
+}
@@ -0,0 +1,208 @@
+//! Represents a streaming JSON Lines extractor or responder.
+//!
+//! [JSON Lines]: <https://jsonlines.org>
+
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    header::{HeaderValue, CONTENT_TYPE},
+    Body, Bytes, BytesMut, FromRequest, IntoResponse, Request, RequestExt, Response, ResponseExt,
+    Result,
+};
+
+use super::{Payload, PayloadError};
+
+/// The media type of JSON Lines.
+const MIME: &str = "application/x-ndjson";
+
+/// Extracts a stream of the JSON values separated by newlines from the body of a request,
+/// or responds a stream of the values as `application/x-ndjson`.
+///
+/// The values are decoded incrementally, and each line is limited by the `json-lines` of the
+/// [`Limits`][super::Limits], by default 1MB.
+///
+/// # Examples
+///
+/// ```ignore
+/// async fn ingest(mut req: Request) -> Result<JsonLines<Report>> {
+///     let mut lines = req.extract::<JsonLines<Event>>().await?;
+///     let mut report = Report::default();
+///     while let Some(event) = lines.next().await {
+///         report.add(event?);
+///     }
+///     Ok(JsonLines::new(futures_util::stream::once(async { report })))
+/// }
+/// ```
+pub struct JsonLines<T> {
+    stream: BoxStream<'static, Result<T, PayloadError>>,
+}
+
+impl<T> JsonLines<T> {
+    /// Creates a new `JsonLines` from a stream of values.
+    pub fn new<S>(stream: S) -> Self
+    where
+        T: Send + 'static,
+        S: Stream<Item = T> + Send + 'static,
+    {
+        Self {
+            stream: stream.map(Ok).boxed(),
+        }
+    }
+
+    /// Decodes the values from the body, each line is limited by the `limit`.
+    fn decode(body: Body, limit: usize) -> Self
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let decoder = Decoder {
+            body,
+            buf: BytesMut::new(),
+            limit,
+            eof: false,
+            done: false,
+        };
+        Self {
+            stream: stream::unfold(decoder, |mut decoder| async move {
+                let item = decoder.next().await?;
+                Some((item, decoder))
+            })
+            .boxed(),
+        }
+    }
+}
+
+impl<T> Stream for JsonLines<T> {
+    type Item = Result<T, PayloadError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+impl<T> fmt::Debug for JsonLines<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonLines").finish_non_exhaustive()
+    }
+}
+
+impl<T> Payload for JsonLines<T> {
+    const NAME: &'static str = "json-lines";
+
+    // 1MB
+    const LIMIT: u64 = 1024 * 1024;
+
+    fn detect(m: &mime::Mime) -> bool {
+        m.type_() == mime::APPLICATION
+            && matches!(
+                m.subtype().as_str(),
+                "x-ndjson" | "ndjson" | "jsonl" | "x-jsonlines" | "jsonlines"
+            )
+    }
+
+    fn mime() -> mime::Mime {
+        MIME.parse().expect("valid media type")
+    }
+}
+
+impl<T> FromRequest for JsonLines<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    type Error = PayloadError;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        <Self as Payload>::check_type(req.content_type())?;
+
+        #[cfg(feature = "limits")]
+        let limit = req
+            .extensions()
+            .get::<super::Limits>()
+            .and_then(|limits| limits.get(<Self as Payload>::NAME));
+        #[cfg(not(feature = "limits"))]
+        let limit = None;
+
+        Ok(Self::decode(
+            req.incoming()?,
+            usize::try_from(<Self as Payload>::limit(limit)).unwrap_or(usize::MAX),
+        ))
+    }
+}
+
+/// Responds with a stream of JSON Lines, the stream is ended by the first error.
+impl<T> IntoResponse for JsonLines<T>
+where
+    T: Serialize + Send + 'static,
+{
+    fn into_response(self) -> Response {
+        let mut resp = Response::stream(self.stream.map(|item| {
+            item.and_then(|value| {
+                let mut buf = serde_json::to_vec(&value)?;
+                buf.push(b'\n');
+                Ok(Bytes::from(buf))
+            })
+        }));
+        resp.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(MIME));
+        resp
+    }
+}
+
+/// Splits the body by newlines and decodes the lines.
+struct Decoder {
+    body: Body,
+    buf: BytesMut,
+    limit: usize,
+    eof: bool,
+    done: bool,
+}
+
+impl Decoder {
+    async fn next<T>(&mut self) -> Option<Result<T, PayloadError>>
+    where
+        T: DeserializeOwned,
+    {
+        while !self.done {
+            let line = if let Some(n) = self.buf.iter().position(|b| *b == b'\n') {
+                self.buf.split_to(n + 1)
+            } else if self.buf.len() > self.limit {
+                self.done = true;
+                return Some(Err(PayloadError::TooLarge));
+            } else if self.eof {
+                self.done = true;
+                self.buf.split()
+            } else {
+                match self.body.next().await {
+                    Some(Ok(bytes)) => self.buf.extend_from_slice(&bytes),
+                    Some(Err(_)) => {
+                        self.done = true;
+                        return Some(Err(PayloadError::Read));
+                    }
+                    None => self.eof = true,
+                }
+                continue;
+            };
+
+            let Some(start) = line.iter().position(|b| !b.is_ascii_whitespace()) else {
+                continue;
+            };
+            let end = line
+                .iter()
+                .rposition(|b| !b.is_ascii_whitespace())
+                .map_or(start, |n| n + 1);
+            let line = &line[start..end];
+            if line.len() > self.limit {
+                self.done = true;
+                return Some(Err(PayloadError::TooLarge));
+            }
+            return Some(serde_json::from_slice(line).map_err(PayloadError::Json));
+        }
+        None
+    }
+}
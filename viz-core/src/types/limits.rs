@@ -1,14 +1,29 @@
 use std::{convert::Infallible, sync::Arc};
 
-use crate::{FromRequest, Request, RequestLimitsExt};
+use crate::{FromRequest, FromRequestParts, Request, RequestLimitsExt};
 
 #[cfg(feature = "form")]
 use super::Form;
 
 #[cfg(feature = "json")]
-use super::Json;
+use super::{Json, JsonLines};
 
-#[cfg(any(feature = "form", feature = "json"))]
+#[cfg(feature = "msgpack")]
+use super::MsgPack;
+
+#[cfg(feature = "cbor")]
+use super::Cbor;
+
+#[cfg(feature = "protobuf")]
+use super::Protobuf;
+
+#[cfg(any(
+    feature = "form",
+    feature = "json",
+    feature = "msgpack",
+    feature = "cbor",
+    feature = "protobuf"
+))]
 use super::Payload;
 
 /// Extracts the limits settings.
@@ -27,9 +42,24 @@ impl Default for Limits {
         #[cfg(feature = "json")]
         let limits = limits.set(<Json as Payload>::NAME, <Json as Payload>::LIMIT);
 
+        #[cfg(feature = "json")]
+        let limits = limits.set(
+            <JsonLines<()> as Payload>::NAME,
+            <JsonLines<()> as Payload>::LIMIT,
+        );
+
         #[cfg(feature = "form")]
         let limits = limits.set(<Form as Payload>::NAME, <Form as Payload>::LIMIT);
 
+        #[cfg(feature = "msgpack")]
+        let limits = limits.set(<MsgPack as Payload>::NAME, <MsgPack as Payload>::LIMIT);
+
+        #[cfg(feature = "cbor")]
+        let limits = limits.set(<Cbor as Payload>::NAME, <Cbor as Payload>::LIMIT);
+
+        #[cfg(feature = "protobuf")]
+        let limits = limits.set(<Protobuf as Payload>::NAME, <Protobuf as Payload>::LIMIT);
+
         limits.sort()
     }
 }
@@ -81,10 +111,18 @@ impl Limits {
     }
 }
 
+impl FromRequestParts for Limits {
+    type Error = Infallible;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        Ok(req.limits().clone())
+    }
+}
+
 impl FromRequest for Limits {
     type Error = Infallible;
 
     async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
-        Ok(req.limits().clone())
+        Self::extract_parts(req).await
     }
 }
@@ -0,0 +1,119 @@
+//! Represents a `MessagePack` extractor or responder.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{FromRequest, IntoResponse, Request, RequestExt, Response, ResponseExt, Result};
+
+#[cfg(feature = "limits")]
+use crate::RequestLimitsExt;
+
+use super::{Payload, PayloadError};
+
+/// The media type of `MessagePack`.
+const MIME: &str = "application/msgpack";
+
+/// Extracts `MessagePack` data from the body of a request, or responds it to response.
+pub struct MsgPack<T = ()>(pub T);
+
+impl<T> MsgPack<T> {
+    /// Create new `MsgPack` instance.
+    #[inline]
+    pub fn new(data: T) -> Self {
+        MsgPack(data)
+    }
+
+    /// Consumes the `MsgPack`, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Clone for MsgPack<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        MsgPack(self.0.clone())
+    }
+}
+
+impl<T> AsRef<T> for MsgPack<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Deref for MsgPack<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MsgPack<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for MsgPack<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T> Payload for MsgPack<T> {
+    const NAME: &'static str = "msgpack";
+
+    // 1MB
+    const LIMIT: u64 = 1024 * 1024;
+
+    fn detect(m: &mime::Mime) -> bool {
+        m.type_() == mime::APPLICATION
+            && matches!(
+                m.subtype().as_str(),
+                "msgpack" | "x-msgpack" | "vnd.msgpack"
+            )
+    }
+
+    fn mime() -> mime::Mime {
+        MIME.parse().expect("valid media type")
+    }
+}
+
+impl<T> FromRequest for MsgPack<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Error = PayloadError;
+
+    #[inline]
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        #[cfg(feature = "limits")]
+        if req.extensions().get::<super::Limits>().is_some() {
+            return req.msgpack_with_limit().await.map(Self);
+        }
+        req.msgpack().await.map(Self)
+    }
+}
+
+/// Responds with `MessagePack` Data.
+impl<T> IntoResponse for MsgPack<T>
+where
+    T: serde::Serialize,
+{
+    fn into_response(self) -> Response {
+        match Response::msgpack(self.0) {
+            Ok(res) => res,
+            Err(err) => err.into_response(),
+        }
+    }
+}
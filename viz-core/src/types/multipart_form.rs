@@ -0,0 +1,365 @@
+//! Represents a typed multipart form extractor.
+
+mod de;
+mod temp_file;
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    path::PathBuf,
+};
+
+use futures_util::TryStreamExt;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    Error, FromRequest, IntoResponse, Request, RequestExt, Response, StatusCode, ThisError,
+};
+
+use super::{MultipartError, PayloadError};
+
+use de::FormDeserializer;
+
+pub use temp_file::TempFile;
+
+/// Extracts the text fields of the multipart body into the type `T`, and the file parts into
+/// the [`MultipartFiles`].
+///
+/// The text fields are deserialized as the values, the repeated fields as the sequences.
+/// The files are checked and spooled by the [`MultipartFormConfig`] which is read from the
+/// extensions of the request, then they are taken by the names of the fields.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Upload {
+///     title: String,
+///     tags: Vec<String>,
+/// }
+///
+/// async fn upload(mut req: Request) -> Result<String> {
+///     let MultipartForm(upload, mut files) = req.extract::<MultipartForm<Upload>>().await?;
+///     for (i, photo) in files.take_all("photos").into_iter().enumerate() {
+///         // The file name is reduced to its final component, so it stays in `uploads`.
+///         let name = photo.file_name().map_or_else(|| format!("photo-{i}"), Into::into);
+///         photo.persist(Path::new("uploads").join(name)).await?;
+///     }
+///     let avatar = files.take("avatar");
+///     Ok(upload.title)
+/// }
+/// ```
+pub struct MultipartForm<T = ()>(pub T, pub MultipartFiles);
+
+impl<T> MultipartForm<T> {
+    /// Create new `MultipartForm` instance without files.
+    #[inline]
+    pub fn new(data: T) -> Self {
+        MultipartForm(data, MultipartFiles::default())
+    }
+
+    /// Consumes the `MultipartForm`, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> AsRef<T> for MultipartForm<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Deref for MultipartForm<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MultipartForm<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for MultipartForm<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MultipartForm")
+            .field(&self.0)
+            .field(&self.1)
+            .finish()
+    }
+}
+
+/// The file parts of the [`MultipartForm`], which are grouped by the names of the fields.
+///
+/// The files which are not taken are removed when it is dropped.
+#[derive(Debug, Default)]
+pub struct MultipartFiles(Vec<(String, Vec<TempFile>)>);
+
+impl MultipartFiles {
+    /// Takes the first file of the field.
+    pub fn take(&mut self, name: &str) -> Option<TempFile> {
+        let files = self.get_mut(name)?;
+        (!files.is_empty()).then(|| files.remove(0))
+    }
+
+    /// Takes all files of the field.
+    pub fn take_all(&mut self, name: &str) -> Vec<TempFile> {
+        self.get_mut(name).map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Checks the files are all taken or not.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|(_, files)| files.is_empty())
+    }
+
+    fn get_mut(&mut self, name: &str) -> Option<&mut Vec<TempFile>> {
+        self.0
+            .iter_mut()
+            .find_map(|(n, files)| (n == name).then_some(files))
+    }
+
+    fn push(&mut self, name: &str, file: TempFile) {
+        match self.get_mut(name) {
+            Some(files) => files.push(file),
+            None => self.0.push((name.to_string(), vec![file])),
+        }
+    }
+}
+
+impl<T> FromRequest for MultipartForm<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = MultipartFormError;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        let config = req
+            .extensions()
+            .get::<MultipartFormConfig>()
+            .cloned()
+            .unwrap_or_default();
+
+        #[cfg(feature = "limits")]
+        let mut form = if req.extensions().get::<super::Limits>().is_some() {
+            crate::RequestLimitsExt::multipart_with_limit(req).await?
+        } else {
+            req.multipart().await?
+        };
+        #[cfg(not(feature = "limits"))]
+        let mut form = req.multipart().await?;
+
+        let mut fields = Vec::<(String, Vec<String>)>::new();
+        let mut files = MultipartFiles::default();
+
+        while let Some(mut field) = form.try_next().await? {
+            match field.filename.as_deref() {
+                // An empty file input of the HTML forms.
+                Some("") => field.ignore().await?,
+                Some(_) => {
+                    let rule = config.rule(&field.name);
+                    if !rule.allows(field.content_type.as_ref()) {
+                        return Err(MultipartFormError::UnsupportedType(field.name.clone()));
+                    }
+                    let file = TempFile::spool(
+                        &mut field,
+                        rule.max_size,
+                        config.memory_threshold,
+                        &config.temp_dir,
+                    )
+                    .await?;
+                    files.push(&field.name, file);
+                }
+                None => {
+                    let text = String::from_utf8(field.bytes().await?.to_vec())
+                        .map_err(PayloadError::Utf8)?;
+                    match fields.iter_mut().find(|(name, _)| *name == field.name) {
+                        Some((_, texts)) => texts.push(text),
+                        None => fields.push((field.name.clone(), vec![text])),
+                    }
+                }
+            }
+        }
+
+        T::deserialize(FormDeserializer(&fields))
+            .map(|data| Self(data, files))
+            .map_err(MultipartFormError::Deserialize)
+    }
+}
+
+/// The rule of the files, limits the size and the media types.
+#[derive(Debug, Clone, Default)]
+pub struct FileRule {
+    max_size: Option<u64>,
+    types: Vec<mime::Mime>,
+}
+
+impl FileRule {
+    /// Creates a new rule, the size and the media types are unlimited.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size of each file.
+    #[must_use]
+    pub fn max_size(mut self, max: u64) -> Self {
+        self.max_size.replace(max);
+        self
+    }
+
+    /// Allows the media type, e.g. `image/png` or `image/*`.
+    #[must_use]
+    pub fn allow(mut self, m: mime::Mime) -> Self {
+        self.types.push(m);
+        self
+    }
+
+    fn allows(&self, m: Option<&mime::Mime>) -> bool {
+        self.types.is_empty()
+            || m.is_some_and(|m| {
+                self.types.iter().any(|t| {
+                    t.type_() == m.type_()
+                        && (t.subtype() == mime::STAR || t.subtype() == m.subtype())
+                })
+            })
+    }
+}
+
+/// A configuration for the [`MultipartForm`].
+#[derive(Debug, Clone)]
+pub struct MultipartFormConfig {
+    memory_threshold: usize,
+    temp_dir: PathBuf,
+    rule: FileRule,
+    rules: Vec<(String, FileRule)>,
+}
+
+impl MultipartFormConfig {
+    /// By default 256KB.
+    pub const MEMORY_THRESHOLD: usize = 1024 * 256;
+
+    /// Creates a new `MultipartFormConfig`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size above which the files are spooled to the temporary directory.
+    #[must_use]
+    pub fn memory_threshold(mut self, threshold: usize) -> Self {
+        self.memory_threshold = threshold;
+        self
+    }
+
+    /// Sets the temporary directory, [`std::env::temp_dir`] by default.
+    #[must_use]
+    pub fn temp_dir<P>(mut self, dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.temp_dir = dir.into();
+        self
+    }
+
+    /// Sets the maximum size of each file of all fields.
+    #[must_use]
+    pub fn max_file_size(mut self, max: u64) -> Self {
+        self.rule = self.rule.max_size(max);
+        self
+    }
+
+    /// Allows the media type of the files of all fields.
+    #[must_use]
+    pub fn allow(mut self, m: mime::Mime) -> Self {
+        self.rule = self.rule.allow(m);
+        self
+    }
+
+    /// Sets the rule of the files of the field, instead of the rule of all fields.
+    #[must_use]
+    pub fn field<S>(mut self, name: S, rule: FileRule) -> Self
+    where
+        S: Into<String>,
+    {
+        self.rules.push((name.into(), rule));
+        self
+    }
+
+    fn rule(&self, name: &str) -> &FileRule {
+        self.rules
+            .iter()
+            .find_map(|(n, rule)| (n == name).then_some(rule))
+            .unwrap_or(&self.rule)
+    }
+}
+
+impl Default for MultipartFormConfig {
+    fn default() -> Self {
+        Self {
+            memory_threshold: Self::MEMORY_THRESHOLD,
+            temp_dir: std::env::temp_dir(),
+            rule: FileRule::default(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Rejects an error when the multipart form extraction fails.
+#[derive(ThisError, Debug)]
+pub enum MultipartFormError {
+    /// The payload error, e.g. the media type is not `multipart/form-data`.
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+
+    /// The multipart error.
+    #[error(transparent)]
+    Multipart(#[from] MultipartError),
+
+    /// 413
+    #[error("file of the field `{0}` is too large")]
+    FileTooLarge(String),
+
+    /// 415
+    #[error("file type of the field `{0}` is not allowed")]
+    UnsupportedType(String),
+
+    /// 400
+    #[error("failed to deserialize multipart form, {0}")]
+    Deserialize(serde::de::value::Error),
+
+    /// 500
+    #[error("failed to spool the file, {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl IntoResponse for MultipartFormError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Payload(e) => e.into_response(),
+            Self::Multipart(e) => e.into_response(),
+            Self::FileTooLarge(_) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()).into_response()
+            }
+            Self::UnsupportedType(_) => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string()).into_response()
+            }
+            Self::Deserialize(_) => (StatusCode::BAD_REQUEST, self.to_string()).into_response(),
+            Self::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response(),
+        }
+    }
+}
+
+impl From<MultipartFormError> for Error {
+    fn from(e: MultipartFormError) -> Self {
+        e.into_error()
+    }
+}
@@ -0,0 +1,291 @@
+use serde::de::{
+    self, DeserializeSeed, Deserializer, Error as _, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+
+macro_rules! parse_value {
+    ($trait_fn:ident, $visit_fn:ident) => {
+        fn $trait_fn<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            let text = self.part;
+            visitor.$visit_fn(text.parse().map_err(|e| {
+                de::value::Error::custom(format!(
+                    "invalid value `{text}` of the field `{}`, {e}",
+                    self.name
+                ))
+            })?)
+        }
+    };
+}
+
+/// Deserializes the text parts which are grouped by the names.
+pub(super) struct FormDeserializer<'de>(pub(super) &'de [(String, Vec<String>)]);
+
+impl<'de> Deserializer<'de> for FormDeserializer<'de> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(FormMap {
+            fields: self.0.iter(),
+            parts: None,
+        })
+    }
+
+    /// Only the files are extracted.
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct FormMap<'de, I> {
+    fields: I,
+    parts: Option<(&'de str, &'de [String])>,
+}
+
+impl<'de, I> MapAccess<'de> for FormMap<'de, I>
+where
+    I: Iterator<Item = &'de (String, Vec<String>)>,
+{
+    type Error = de::value::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.fields.next() {
+            Some((name, parts)) => {
+                self.parts = Some((name, parts));
+                seed.deserialize(name.as_str().into_deserializer())
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (name, parts) = self
+            .parts
+            .take()
+            .ok_or_else(|| de::value::Error::custom("value is missing"))?;
+        seed.deserialize(PartsDeserializer { name, parts })
+    }
+}
+
+/// Deserializes the parts of a field, a sequence or a single part.
+struct PartsDeserializer<'de> {
+    name: &'de str,
+    parts: &'de [String],
+}
+
+impl<'de> PartsDeserializer<'de> {
+    fn single(self) -> Result<PartDeserializer<'de>, de::value::Error> {
+        match self.parts {
+            [part] => Ok(PartDeserializer {
+                name: self.name,
+                part,
+            }),
+            _ => Err(de::value::Error::custom(format!(
+                "expected a single value of the field `{}`",
+                self.name
+            ))),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for PartsDeserializer<'de> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.parts.len() > 1 {
+            self.deserialize_seq(visitor)
+        } else {
+            self.single()?.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if matches!(self.parts, [part] if part.is_empty()) {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(PartsSeq {
+            name: self.name,
+            parts: self.parts.iter(),
+        })
+    }
+
+    fn deserialize_tuple<V>(self, _: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct map struct identifier ignored_any
+    }
+}
+
+struct PartsSeq<'de, I> {
+    name: &'de str,
+    parts: I,
+}
+
+impl<'de, I> SeqAccess<'de> for PartsSeq<'de, I>
+where
+    I: Iterator<Item = &'de String>,
+{
+    type Error = de::value::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.parts.next() {
+            Some(part) => seed
+                .deserialize(PartDeserializer {
+                    name: self.name,
+                    part,
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Deserializes a text part.
+struct PartDeserializer<'de> {
+    name: &'de str,
+    part: &'de str,
+}
+
+impl<'de> Deserializer<'de> for PartDeserializer<'de> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str(self.part)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.part.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self.part.into_deserializer())
+    }
+
+    parse_value!(deserialize_bool, visit_bool);
+    parse_value!(deserialize_i8, visit_i8);
+    parse_value!(deserialize_i16, visit_i16);
+    parse_value!(deserialize_i32, visit_i32);
+    parse_value!(deserialize_i64, visit_i64);
+    parse_value!(deserialize_i128, visit_i128);
+    parse_value!(deserialize_u8, visit_u8);
+    parse_value!(deserialize_u16, visit_u16);
+    parse_value!(deserialize_u32, visit_u32);
+    parse_value!(deserialize_u64, visit_u64);
+    parse_value!(deserialize_u128, visit_u128);
+    parse_value!(deserialize_f32, visit_f32);
+    parse_value!(deserialize_f64, visit_f64);
+    parse_value!(deserialize_char, visit_char);
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
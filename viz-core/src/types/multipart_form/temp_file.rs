@@ -0,0 +1,169 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use form_data::Field;
+use futures_util::TryStreamExt;
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+
+use crate::{Body, Bytes, BytesMut};
+
+use super::MultipartFormError;
+
+enum Inner {
+    Memory(Bytes),
+    Disk(NamedTempFile),
+}
+
+/// A file part of the [`MultipartForm`][super::MultipartForm], it is kept in memory below the
+/// threshold, otherwise it is spooled to the temporary directory.
+///
+/// The temporary file is removed when it is dropped.
+pub struct TempFile {
+    file_name: Option<String>,
+    content_type: Option<mime::Mime>,
+    size: u64,
+    inner: Inner,
+}
+
+impl TempFile {
+    /// Spools the field, the size of the file is limited by the `max_size`.
+    pub(super) async fn spool(
+        field: &mut Field<Body>,
+        max_size: Option<u64>,
+        threshold: usize,
+        dir: &Path,
+    ) -> Result<Self, MultipartFormError> {
+        let mut size = 0;
+        let mut buf = BytesMut::new();
+        let mut disk: Option<(NamedTempFile, tokio::fs::File)> = None;
+
+        while let Some(chunk) = field.try_next().await? {
+            size += chunk.len() as u64;
+            if max_size.is_some_and(|max| size > max) {
+                return Err(MultipartFormError::FileTooLarge(field.name.clone()));
+            }
+
+            if let Some((_, file)) = &mut disk {
+                file.write_all(&chunk).await?;
+            } else if buf.len() + chunk.len() > threshold {
+                let dir = dir.to_path_buf();
+                let (temp, file) = tokio::task::spawn_blocking(move || {
+                    let temp = NamedTempFile::new_in(dir)?;
+                    let file = temp.reopen()?;
+                    std::io::Result::Ok((temp, file))
+                })
+                .await
+                .map_err(std::io::Error::other)??;
+                let mut file = tokio::fs::File::from_std(file);
+                file.write_all(&buf).await?;
+                file.write_all(&chunk).await?;
+                disk = Some((temp, file));
+            } else {
+                buf.extend_from_slice(&chunk);
+            }
+        }
+
+        let inner = match disk {
+            Some((temp, mut file)) => {
+                file.flush().await?;
+                Inner::Disk(temp)
+            }
+            None => Inner::Memory(buf.freeze()),
+        };
+
+        Ok(Self {
+            file_name: field.filename.as_deref().and_then(sanitize).map(Into::into),
+            content_type: field.content_type.clone(),
+            size,
+            inner,
+        })
+    }
+
+    /// The file name which is provided by the client, only the final component is kept, so it
+    /// can not escape from the directory it is joined to.
+    #[must_use]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// The media type which is provided by the client.
+    #[must_use]
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// The size of the file.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The path of the temporary file, `None` if the file is kept in memory.
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        match &self.inner {
+            Inner::Memory(_) => None,
+            Inner::Disk(temp) => Some(temp.path()),
+        }
+    }
+
+    /// Reads the content of the file.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`std::io::Error`] if the temporary file cannot be read.
+    pub async fn bytes(&self) -> std::io::Result<Bytes> {
+        match &self.inner {
+            Inner::Memory(bytes) => Ok(bytes.clone()),
+            Inner::Disk(temp) => tokio::fs::read(temp.path()).await.map(Bytes::from),
+        }
+    }
+
+    /// Persists the file to the path, then the temporary file is removed.
+    ///
+    /// The temporary file is renamed to the path, or copied if they are on different devices.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`std::io::Error`] if the file cannot be written.
+    pub async fn persist<P>(self, path: P) -> std::io::Result<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        match self.inner {
+            Inner::Memory(bytes) => tokio::fs::write(&path, bytes).await?,
+            Inner::Disk(temp) => {
+                let target = path.clone();
+                let persisted = tokio::task::spawn_blocking(move || temp.persist(target))
+                    .await
+                    .map_err(std::io::Error::other)?;
+                if let Err(e) = persisted {
+                    tokio::fs::copy(e.file.path(), &path).await?;
+                }
+            }
+        }
+        Ok(path)
+    }
+}
+
+/// Keeps the final component of the file name, the empty, `.` and `..` names are dropped.
+fn sanitize(name: &str) -> Option<&str> {
+    name.rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !matches!(*name, "" | "." | ".."))
+}
+
+impl fmt::Debug for TempFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TempFile")
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .field("size", &self.size)
+            .field("path", &self.path())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,158 @@
+//! Represents a content negotiation responder.
+
+use serde::Serialize;
+
+use crate::{
+    header::{HeaderValue, VARY},
+    IntoResponse, Response, ResponseExt, StatusCode,
+};
+
+use super::{Accept, Json, Payload};
+
+/// The representations of the [`Negotiate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    #[cfg(feature = "cbor")]
+    Cbor,
+    #[cfg(feature = "form")]
+    Form,
+    Text,
+}
+
+impl Format {
+    /// The available formats, the former is preferred.
+    const ALL: &'static [Self] = &[
+        Self::Json,
+        #[cfg(feature = "msgpack")]
+        Self::MsgPack,
+        #[cfg(feature = "cbor")]
+        Self::Cbor,
+        #[cfg(feature = "form")]
+        Self::Form,
+        Self::Text,
+    ];
+
+    fn mime(self) -> mime::Mime {
+        match self {
+            Self::Json => mime::APPLICATION_JSON,
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => <super::MsgPack as Payload>::mime(),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => <super::Cbor as Payload>::mime(),
+            #[cfg(feature = "form")]
+            Self::Form => <super::Form as Payload>::mime(),
+            Self::Text => mime::TEXT_PLAIN_UTF_8,
+        }
+    }
+
+    fn detect(self, m: &mime::Mime) -> bool {
+        match self {
+            Self::Json => <Json as Payload>::detect(m),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => <super::MsgPack as Payload>::detect(m),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => <super::Cbor as Payload>::detect(m),
+            #[cfg(feature = "form")]
+            Self::Form => <super::Form as Payload>::detect(m),
+            Self::Text => m.type_() == mime::TEXT && m.subtype() == mime::PLAIN,
+        }
+    }
+
+    fn render<T>(self, value: &T) -> Response
+    where
+        T: Serialize,
+    {
+        match self {
+            Self::Json => Response::json(value).unwrap_or_else(IntoResponse::into_response),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => Response::msgpack(value).unwrap_or_else(IntoResponse::into_response),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => Response::cbor(value).unwrap_or_else(IntoResponse::into_response),
+            #[cfg(feature = "form")]
+            Self::Form => match serde_urlencoded::to_string(value) {
+                Ok(body) => Response::with(
+                    http_body_util::Full::from(body),
+                    mime::APPLICATION_WWW_FORM_URLENCODED.as_ref(),
+                ),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            },
+            Self::Text => match serde_json::to_value(value) {
+                Ok(serde_json::Value::String(s)) => Response::text(s),
+                Ok(value) => Response::text(value.to_string()),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            },
+        }
+    }
+}
+
+/// Responds the value in the representation which is best matched by the `Accept` header,
+/// or responds `406 Not Acceptable` if nothing matches.
+///
+/// The JSON is preferred, then the `MessagePack`, the CBOR, the form-urlencoded and
+/// the plain text.
+///
+/// # Examples
+///
+/// ```ignore
+/// async fn show(mut req: Request) -> Result<Negotiate<User>> {
+///     let accept = req.extract::<Accept>().await?;
+///     Ok(Negotiate::new(accept, User { id: 1 }))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Negotiate<T> {
+    accept: Accept,
+    value: T,
+}
+
+impl<T> Negotiate<T> {
+    /// Creates a new `Negotiate` instance with the [`Accept`] of the request.
+    #[inline]
+    pub fn new(accept: Accept, value: T) -> Self {
+        Self { accept, value }
+    }
+
+    /// Consumes the Negotiate, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Picks the format, a concrete media range is detected as the payload does.
+    fn format(&self) -> Option<Format> {
+        if self.accept.is_empty() {
+            return Format::ALL.first().copied();
+        }
+        self.accept
+            .iter()
+            .filter(|range| range.q > 0.0)
+            .find_map(|range| {
+                Format::ALL.iter().copied().find(|format| {
+                    if range.mime.type_() == mime::STAR || range.mime.subtype() == mime::STAR {
+                        let m = format.mime();
+                        range.matches(&m) && self.accept.quality(&m) > 0.0
+                    } else {
+                        format.detect(&range.mime)
+                    }
+                })
+            })
+    }
+}
+
+impl<T> IntoResponse for Negotiate<T>
+where
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        let mut resp = match self.format() {
+            Some(format) => format.render(&self.value),
+            None => StatusCode::NOT_ACCEPTABLE.into_response(),
+        };
+        resp.headers_mut()
+            .append(VARY, HeaderValue::from_static("accept"));
+        resp
+    }
+}
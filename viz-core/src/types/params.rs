@@ -11,7 +11,8 @@ use std::{
 use serde::de::DeserializeOwned;
 
 use crate::{
-    Error, FromRequest, IntoResponse, Request, RequestExt, Response, StatusCode, ThisError,
+    Error, FromRequest, FromRequestParts, IntoResponse, Request, RequestExt, Response, StatusCode,
+    ThisError,
 };
 
 pub(crate) use de::PathDeserializer;
@@ -74,6 +75,34 @@ impl Params {
             .parse()
             .map_err(|e: T::Err| ParamsError::SingleParse(e.to_string()))
     }
+
+    /// Gets single optional parameter by name, it is `None` if the parameter is missing or empty.
+    ///
+    /// # Errors
+    ///
+    /// Throws a `ParamsError`
+    pub fn find_optional<T>(&self, name: &str) -> Result<Option<T>, ParamsError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.iter()
+            .find(|p| p.0 == name && !p.1.is_empty())
+            .map(|p| p.1.parse())
+            .transpose()
+            .map_err(|e: T::Err| ParamsError::SingleParse(e.to_string()))
+    }
+}
+
+impl<T> FromRequestParts for Params<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = ParamsError;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        req.params().map(Params)
+    }
 }
 
 impl<T> FromRequest for Params<T>
@@ -83,7 +112,7 @@ where
     type Error = ParamsError;
 
     async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
-        req.params().map(Params)
+        Self::extract_parts(req).await
     }
 }
 
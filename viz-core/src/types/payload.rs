@@ -34,6 +34,36 @@ pub enum PayloadError {
     #[error("JSON serialize or deserialize faild, {0}")]
     Json(#[from] serde_json::Error),
 
+    /// 400
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack deserialize failed, {0}")]
+    MsgPack(#[from] rmp_serde::decode::Error),
+
+    /// 500
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack serialize failed, {0}")]
+    MsgPackEncode(#[from] rmp_serde::encode::Error),
+
+    /// 400
+    #[cfg(feature = "cbor")]
+    #[error("CBOR deserialize failed, {0}")]
+    Cbor(#[from] ciborium::de::Error<std::io::Error>),
+
+    /// 500
+    #[cfg(feature = "cbor")]
+    #[error("CBOR serialize failed, {0}")]
+    CborEncode(#[from] ciborium::ser::Error<std::io::Error>),
+
+    /// 400
+    #[cfg(feature = "protobuf")]
+    #[error("Protobuf decode failed, {0}")]
+    Protobuf(#[from] prost::DecodeError),
+
+    /// 400
+    #[cfg(feature = "query")]
+    #[error("failed to deserialize query, {0}")]
+    Query(#[from] super::QueryError),
+
     /// 400
     #[cfg(any(feature = "form", feature = "query"))]
     #[error("url decode failed, {0}")]
@@ -68,6 +98,18 @@ impl IntoResponse for PayloadError {
                 | PayloadError::Hyper(_) => StatusCode::BAD_REQUEST,
                 #[cfg(feature = "json")]
                 PayloadError::Json(_) => StatusCode::BAD_REQUEST,
+                #[cfg(feature = "msgpack")]
+                PayloadError::MsgPack(_) => StatusCode::BAD_REQUEST,
+                #[cfg(feature = "msgpack")]
+                PayloadError::MsgPackEncode(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                #[cfg(feature = "cbor")]
+                PayloadError::Cbor(_) => StatusCode::BAD_REQUEST,
+                #[cfg(feature = "cbor")]
+                PayloadError::CborEncode(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                #[cfg(feature = "protobuf")]
+                PayloadError::Protobuf(_) => StatusCode::BAD_REQUEST,
+                #[cfg(feature = "query")]
+                PayloadError::Query(_) => StatusCode::BAD_REQUEST,
                 #[cfg(any(feature = "form", feature = "query"))]
                 PayloadError::UrlDecode(_) => StatusCode::BAD_REQUEST,
                 PayloadError::LengthRequired => StatusCode::LENGTH_REQUIRED,
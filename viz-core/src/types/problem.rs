@@ -0,0 +1,167 @@
+//! Represents a problem details responder.
+
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{
+    header::{HeaderValue, CONTENT_TYPE},
+    IntoResponse, Response, ResponseExt, StatusCode,
+};
+
+/// Responds the problem details of an HTTP API error as `application/problem+json`.
+///
+/// See [RFC 9457](https://www.rfc-editor.org/rfc/rfc9457).
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    /// A URI reference that identifies the problem type, `about:blank` by default.
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    /// A short summary of the problem type.
+    pub title: String,
+    /// The HTTP status code.
+    #[serde(serialize_with = "serialize_status")]
+    pub status: StatusCode,
+    /// An explanation specific to this occurrence of the problem.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// A URI reference that identifies the specific occurrence of the problem.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// The extension members.
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+impl Problem {
+    /// The media type of the problem details.
+    pub const CONTENT_TYPE: &'static str = "application/problem+json";
+
+    /// Creates a new problem with the status code, the title is the canonical reason.
+    #[must_use]
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            type_uri: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or("Unknown").to_string(),
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// Sets the problem type URI.
+    #[must_use]
+    pub fn type_uri<S>(mut self, type_uri: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// Sets the title.
+    #[must_use]
+    pub fn title<S>(mut self, title: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the detail.
+    #[must_use]
+    pub fn detail<S>(mut self, detail: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the instance URI.
+    #[must_use]
+    pub fn instance<S>(mut self, instance: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds an extension member, it is ignored if the value cannot be serialized.
+    #[must_use]
+    pub fn extension<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Serialize,
+    {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.extensions.insert(key.into(), value);
+        }
+        self
+    }
+
+    /// Renders as the plain text, the detail or the title.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        self.detail.clone().unwrap_or_else(|| self.title.clone())
+    }
+
+    /// Renders as a simple HTML page.
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let title = format!("{} {}", self.status.as_u16(), escape(&self.title));
+        let detail = self
+            .detail
+            .as_deref()
+            .map(|detail| format!("<p>{}</p>", escape(detail)))
+            .unwrap_or_default();
+        format!(
+            "<!DOCTYPE html><html><head><title>{title}</title></head><body><h1>{title}</h1>{detail}</body></html>"
+        )
+    }
+}
+
+impl From<StatusCode> for Problem {
+    fn from(status: StatusCode) -> Self {
+        Self::new(status)
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = self.status;
+        let mut resp = match Response::json(&self) {
+            Ok(resp) => resp,
+            Err(err) => return err.into_response(),
+        };
+        *resp.status_mut() = status;
+        resp.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(Self::CONTENT_TYPE));
+        resp
+    }
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_status<S>(status: &StatusCode, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_u16(status.as_u16())
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
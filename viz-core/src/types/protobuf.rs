@@ -0,0 +1,116 @@
+//! Represents a Protocol Buffers extractor or responder.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{FromRequest, IntoResponse, Request, RequestExt, Response, ResponseExt, Result};
+
+#[cfg(feature = "limits")]
+use crate::RequestLimitsExt;
+
+use super::{Payload, PayloadError};
+
+/// The media type of Protocol Buffers.
+const MIME: &str = "application/x-protobuf";
+
+/// Extracts Protocol Buffers data from the body of a request, or responds it to response.
+pub struct Protobuf<T = ()>(pub T);
+
+impl<T> Protobuf<T> {
+    /// Create new `Protobuf` instance.
+    #[inline]
+    pub fn new(data: T) -> Self {
+        Protobuf(data)
+    }
+
+    /// Consumes the `Protobuf`, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Clone for Protobuf<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Protobuf(self.0.clone())
+    }
+}
+
+impl<T> AsRef<T> for Protobuf<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Deref for Protobuf<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Protobuf<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for Protobuf<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T> Payload for Protobuf<T> {
+    const NAME: &'static str = "protobuf";
+
+    // 1MB
+    const LIMIT: u64 = 1024 * 1024;
+
+    fn detect(m: &mime::Mime) -> bool {
+        m.type_() == mime::APPLICATION
+            && matches!(
+                m.subtype().as_str(),
+                "x-protobuf" | "protobuf" | "vnd.google.protobuf"
+            )
+    }
+
+    fn mime() -> mime::Mime {
+        MIME.parse().expect("valid media type")
+    }
+}
+
+impl<T> FromRequest for Protobuf<T>
+where
+    T: prost::Message + Default,
+{
+    type Error = PayloadError;
+
+    #[inline]
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        #[cfg(feature = "limits")]
+        if req.extensions().get::<super::Limits>().is_some() {
+            return req.protobuf_with_limit().await.map(Self);
+        }
+        req.protobuf().await.map(Self)
+    }
+}
+
+/// Responds with Protocol Buffers Data.
+impl<T> IntoResponse for Protobuf<T>
+where
+    T: prost::Message,
+{
+    fn into_response(self) -> Response {
+        Response::protobuf(&self.0)
+    }
+}
@@ -1,5 +1,7 @@
 //! Represents a query extractor.
 
+mod de;
+
 use std::{
     fmt,
     ops::{Deref, DerefMut},
@@ -7,7 +9,9 @@ use std::{
 
 use serde::de::DeserializeOwned;
 
-use crate::{types::PayloadError, FromRequest, Request, RequestExt, Result};
+use crate::{types::PayloadError, FromRequest, FromRequestParts, Request, RequestExt, Result};
+
+pub use de::{ArrayFormat, QueryConfig, QueryError};
 
 /// Extracts the data from the query string of a URL.
 pub struct Query<T = ()>(pub T);
@@ -64,6 +68,17 @@ where
     }
 }
 
+impl<T> FromRequestParts for Query<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = PayloadError;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        req.query().map(Self)
+    }
+}
+
 impl<T> FromRequest for Query<T>
 where
     T: DeserializeOwned,
@@ -71,6 +86,113 @@ where
     type Error = PayloadError;
 
     async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
-        req.query().map(Self)
+        Self::extract_parts(req).await
+    }
+}
+
+/// Extracts the data from the query string of a URL, supports the repeated keys and the nested
+/// keys in bracket notation, e.g. `tags=a&tags=b&filter[name]=viz`.
+///
+/// The [`QueryConfig`] is read from the extensions of the request, it can be provided by
+/// the `State` middleware.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Filter {
+///     name: Option<String>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Search {
+///     tags: Vec<String>,
+///     filter: Filter,
+/// }
+///
+/// async fn search(mut req: Request) -> Result<String> {
+///     let NestedQuery(search) = req.extract::<NestedQuery<Search>>().await?;
+///     Ok(search.tags.join(","))
+/// }
+/// ```
+pub struct NestedQuery<T = ()>(pub T);
+
+impl<T> NestedQuery<T> {
+    /// Create new `NestedQuery` instance.
+    #[inline]
+    pub fn new(data: T) -> Self {
+        NestedQuery(data)
+    }
+
+    /// Consumes the `NestedQuery`, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Clone for NestedQuery<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        NestedQuery(self.0.clone())
+    }
+}
+
+impl<T> AsRef<T> for NestedQuery<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Deref for NestedQuery<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for NestedQuery<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for NestedQuery<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T> FromRequestParts for NestedQuery<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = PayloadError;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        let query = req.query_string().unwrap_or_default();
+        match req.extensions().get::<QueryConfig>() {
+            Some(config) => config.deserialize(query),
+            None => QueryConfig::default().deserialize(query),
+        }
+        .map(Self)
+        .map_err(PayloadError::Query)
+    }
+}
+
+impl<T> FromRequest for NestedQuery<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = PayloadError;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        Self::extract_parts(req).await
     }
 }
@@ -0,0 +1,664 @@
+//! A query string deserializer supports the repeated keys and the bracket notation.
+
+use std::fmt;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, Error as _, IntoDeserializer, MapAccess,
+    SeqAccess, Visitor,
+};
+
+macro_rules! parse_value {
+    ($trait_fn:ident, $visit_fn:ident) => {
+        fn $trait_fn<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            let value = self.value()?;
+            let value = value.parse().map_err(|e| {
+                QueryError::custom(format!("invalid value `{value}`, {e}")).at(&self.path)
+            })?;
+            visitor
+                .$visit_fn(value)
+                .map_err(|e: QueryError| e.at(&self.path))
+        }
+    };
+}
+
+/// The formats of the sequences in the query string.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArrayFormat {
+    /// The repeated keys `tags=a&tags=b`, the empty brackets `tags[]=a&tags[]=b`
+    /// or the indices `tags[0]=a&tags[1]=b`.
+    #[default]
+    Repeat,
+    /// Also splits the values by commas, e.g. `tags=a,b`.
+    Comma,
+}
+
+/// A configuration for the [`NestedQuery`][super::NestedQuery], it is read from
+/// the extensions of the request.
+#[derive(Debug, Clone)]
+pub struct QueryConfig {
+    array_format: ArrayFormat,
+    max_depth: usize,
+}
+
+impl QueryConfig {
+    /// Creates a new `QueryConfig`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the format of the sequences, [`ArrayFormat::Repeat`] by default.
+    #[must_use]
+    pub fn array_format(mut self, format: ArrayFormat) -> Self {
+        self.array_format = format;
+        self
+    }
+
+    /// Sets the maximum depth of the nested keys, `5` by default.
+    #[must_use]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Deserializes the query string into the type `T`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`QueryError`] if the query string is invalid or cannot be deserialized.
+    pub fn deserialize<T>(&self, query: &str) -> Result<T, QueryError>
+    where
+        T: DeserializeOwned,
+    {
+        let root = Node::parse(query, self.max_depth)?;
+        T::deserialize(NodeDeserializer {
+            input: Input::Node(&root),
+            path: String::new(),
+            format: self.array_format,
+        })
+    }
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            array_format: ArrayFormat::default(),
+            max_depth: 5,
+        }
+    }
+}
+
+/// An error of deserializing the query string, with the offending field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    field: Option<String>,
+    message: String,
+}
+
+impl QueryError {
+    /// The offending field, e.g. `filter[age]` or `tags[1]`.
+    #[must_use]
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// The error message without the field.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Sets the field if it is not set by the inner values.
+    fn at(mut self, path: &str) -> Self {
+        if self.field.is_none() && !path.is_empty() {
+            self.field = Some(path.to_string());
+        }
+        self
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "`{field}`: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl de::Error for QueryError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self {
+            field: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// The parsed query string.
+#[derive(Debug)]
+enum Node {
+    Value(String),
+    Seq(Vec<Node>),
+    Map(Vec<(String, Node)>),
+}
+
+impl Node {
+    fn parse(query: &str, max_depth: usize) -> Result<Self, QueryError> {
+        let pairs = serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+            .map_err(QueryError::custom)?;
+
+        let mut root = Vec::new();
+        for (key, value) in pairs {
+            let segments = split(&key);
+            if segments.len() > max_depth + 1 {
+                return Err(QueryError::custom("nested too deeply").at(&key));
+            }
+            insert(&mut root, &segments, value).map_err(|e| e.at(&key))?;
+        }
+
+        Ok(Self::Map(root))
+    }
+
+    fn push(&mut self, node: Self) -> Result<(), QueryError> {
+        match self {
+            Self::Value(_) => {
+                let value = std::mem::replace(self, Self::Seq(Vec::new()));
+                *self = Self::Seq(vec![value, node]);
+            }
+            Self::Seq(nodes) => nodes.push(node),
+            Self::Map(_) => return Err(QueryError::custom("conflicts with the nested keys")),
+        }
+        Ok(())
+    }
+}
+
+/// Splits the key in bracket notation, e.g. `filter[name][]` into `filter`, `name` and an
+/// empty segment.
+fn split(key: &str) -> Vec<&str> {
+    let Some(start) = key.find('[').filter(|n| *n > 0) else {
+        return vec![key];
+    };
+
+    let mut segments = vec![&key[..start]];
+    let mut rest = &key[start..];
+    while let Some(inner) = rest.strip_prefix('[') {
+        let Some(end) = inner.find(']') else {
+            return vec![key];
+        };
+        segments.push(&inner[..end]);
+        rest = &inner[end + 1..];
+    }
+
+    if rest.is_empty() {
+        segments
+    } else {
+        vec![key]
+    }
+}
+
+fn insert(
+    entries: &mut Vec<(String, Node)>,
+    segments: &[&str],
+    value: String,
+) -> Result<(), QueryError> {
+    let Some((name, rest)) = segments.split_first() else {
+        return Ok(());
+    };
+    let entry = entries.iter_mut().position(|(k, _)| k == name);
+
+    match (rest, entry) {
+        ([], None) => entries.push(((*name).to_string(), Node::Value(value))),
+        ([""], None) => entries.push(((*name).to_string(), Node::Seq(vec![Node::Value(value)]))),
+        ([] | [""], Some(i)) => entries[i].1.push(Node::Value(value))?,
+        (["", ..], _) => return Err(QueryError::custom("unsupported nested keys in a sequence")),
+        (_, None) => {
+            let mut nested = Vec::new();
+            insert(&mut nested, rest, value)?;
+            entries.push(((*name).to_string(), Node::Map(nested)));
+        }
+        (_, Some(i)) => match &mut entries[i].1 {
+            Node::Map(nested) => insert(nested, rest, value)?,
+            _ => return Err(QueryError::custom("conflicts with the nested keys")),
+        },
+    }
+
+    Ok(())
+}
+
+/// The input of the deserializer, a node or a part of the value split by commas.
+#[derive(Clone, Copy)]
+enum Input<'de> {
+    Node(&'de Node),
+    Str(&'de str),
+}
+
+struct NodeDeserializer<'de> {
+    input: Input<'de>,
+    path: String,
+    format: ArrayFormat,
+}
+
+impl<'de> NodeDeserializer<'de> {
+    fn child(&self, input: Input<'de>, key: &str) -> Self {
+        Self {
+            input,
+            path: if self.path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}[{key}]", self.path)
+            },
+            format: self.format,
+        }
+    }
+
+    fn value(&self) -> Result<&'de str, QueryError> {
+        match self.input {
+            Input::Str(value) => Ok(value),
+            Input::Node(Node::Value(value)) => Ok(value.as_str()),
+            Input::Node(Node::Seq(nodes)) => match nodes.as_slice() {
+                [Node::Value(value)] => Ok(value.as_str()),
+                _ => Err(QueryError::custom(
+                    "expected a single value, found a sequence",
+                )),
+            },
+            Input::Node(Node::Map(_)) => Err(QueryError::custom(
+                "expected a single value, found the nested keys",
+            )),
+        }
+        .map_err(|e: QueryError| e.at(&self.path))
+    }
+
+    fn items(&self) -> Result<Vec<Input<'de>>, QueryError> {
+        let split = |value: &'de str| match self.format {
+            ArrayFormat::Repeat => vec![Input::Str(value)],
+            ArrayFormat::Comma => value.split(',').map(Input::Str).collect(),
+        };
+
+        Ok(match self.input {
+            Input::Str(value) => split(value),
+            Input::Node(Node::Value(value)) => split(value),
+            Input::Node(Node::Seq(nodes)) => nodes
+                .iter()
+                .flat_map(|node| match node {
+                    Node::Value(value) => split(value),
+                    _ => vec![Input::Node(node)],
+                })
+                .collect(),
+            Input::Node(Node::Map(entries)) => {
+                let mut indexed = entries
+                    .iter()
+                    .map(|(k, node)| k.parse::<usize>().map(|i| (i, Input::Node(node))))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| {
+                        QueryError::custom("expected a sequence, found the nested keys")
+                            .at(&self.path)
+                    })?;
+                indexed.sort_by_key(|(i, _)| *i);
+                indexed.into_iter().map(|(_, input)| input).collect()
+            }
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        match self.input {
+            Input::Str(value) => value.is_empty(),
+            Input::Node(Node::Value(value)) => value.is_empty(),
+            Input::Node(_) => false,
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for NodeDeserializer<'de> {
+    type Error = QueryError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            Input::Str(_) | Input::Node(Node::Value(_)) => self.deserialize_str(visitor),
+            Input::Node(Node::Seq(_)) => self.deserialize_seq(visitor),
+            Input::Node(Node::Map(_)) => self.deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_borrowed_str(self.value()?)
+            .map_err(|e: QueryError| e.at(&self.path))
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_borrowed_bytes(self.value()?.as_bytes())
+            .map_err(|e: QueryError| e.at(&self.path))
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let items = self.items()?;
+        visitor
+            .visit_seq(NodeSeq {
+                items: items.into_iter().enumerate(),
+                parent: &self,
+            })
+            .map_err(|e: QueryError| e.at(&self.path))
+    }
+
+    fn deserialize_tuple<V>(self, _: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let Input::Node(Node::Map(entries)) = self.input else {
+            return Err(QueryError::custom("expected the nested keys").at(&self.path));
+        };
+        visitor
+            .visit_map(NodeMap {
+                entries: entries.iter(),
+                value: None,
+                parent: &self,
+            })
+            .map_err(|e: QueryError| e.at(&self.path))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_enum(self.value()?.into_deserializer())
+            .map_err(|e: QueryError| e.at(&self.path))
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    parse_value!(deserialize_bool, visit_bool);
+    parse_value!(deserialize_i8, visit_i8);
+    parse_value!(deserialize_i16, visit_i16);
+    parse_value!(deserialize_i32, visit_i32);
+    parse_value!(deserialize_i64, visit_i64);
+    parse_value!(deserialize_i128, visit_i128);
+    parse_value!(deserialize_u8, visit_u8);
+    parse_value!(deserialize_u16, visit_u16);
+    parse_value!(deserialize_u32, visit_u32);
+    parse_value!(deserialize_u64, visit_u64);
+    parse_value!(deserialize_u128, visit_u128);
+    parse_value!(deserialize_f32, visit_f32);
+    parse_value!(deserialize_f64, visit_f64);
+    parse_value!(deserialize_char, visit_char);
+}
+
+struct NodeSeq<'a, 'de, I> {
+    items: I,
+    parent: &'a NodeDeserializer<'de>,
+}
+
+impl<'de, I> SeqAccess<'de> for NodeSeq<'_, 'de, I>
+where
+    I: Iterator<Item = (usize, Input<'de>)>,
+{
+    type Error = QueryError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.items.next() {
+            Some((i, input)) => seed
+                .deserialize(self.parent.child(input, &i.to_string()))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct NodeMap<'a, 'de, I> {
+    entries: I,
+    value: Option<(&'de str, &'de Node)>,
+    parent: &'a NodeDeserializer<'de>,
+}
+
+impl<'de, I> MapAccess<'de> for NodeMap<'_, 'de, I>
+where
+    I: Iterator<Item = &'de (String, Node)>,
+{
+    type Error = QueryError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((key, node)) => {
+                self.value = Some((key, node));
+                seed.deserialize(self.parent.child(Input::Str(key), key))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (key, node) = self
+            .value
+            .take()
+            .ok_or_else(|| QueryError::custom("value is missing"))?;
+        seed.deserialize(self.parent.child(Input::Node(node), key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Filter {
+        name: Option<String>,
+        age: Option<u8>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Search {
+        #[serde(default)]
+        tags: Vec<String>,
+        filter: Option<Filter>,
+        page: Option<u32>,
+    }
+
+    #[test]
+    fn repeated_and_nested() {
+        let config = QueryConfig::new();
+
+        let search: Search = config
+            .deserialize("tags=a&tags=b&filter[name]=viz&filter[age]=8&page=")
+            .unwrap();
+        assert_eq!(
+            search,
+            Search {
+                tags: vec!["a".to_string(), "b".to_string()],
+                filter: Some(Filter {
+                    name: Some("viz".to_string()),
+                    age: Some(8)
+                }),
+                page: None,
+            }
+        );
+
+        let search: Search = config.deserialize("tags[]=a&tags[]=b").unwrap();
+        assert_eq!(search.tags, ["a", "b"]);
+
+        let search: Search = config.deserialize("tags[1]=b&tags[0]=a").unwrap();
+        assert_eq!(search.tags, ["a", "b"]);
+
+        let search: Search = config.deserialize("tags=a").unwrap();
+        assert_eq!(search.tags, ["a"]);
+
+        let search: Search = config.deserialize("tags=a,b").unwrap();
+        assert_eq!(search.tags, ["a,b"]);
+
+        let map: HashMap<String, HashMap<String, u8>> =
+            config.deserialize("a[x]=1&a[y]=2").unwrap();
+        assert_eq!(map["a"]["y"], 2);
+    }
+
+    #[test]
+    fn comma() {
+        let config = QueryConfig::new().array_format(ArrayFormat::Comma);
+
+        let search: Search = config.deserialize("tags=a,b&tags=c").unwrap();
+        assert_eq!(search.tags, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn errors() {
+        let config = QueryConfig::new().max_depth(2);
+
+        let err = config.deserialize::<Search>("filter[age]=old").unwrap_err();
+        assert_eq!(err.field(), Some("filter[age]"));
+        assert_eq!(
+            err.to_string(),
+            "`filter[age]`: invalid value `old`, invalid digit found in string"
+        );
+
+        let err = config.deserialize::<Search>("page=1&page=2").unwrap_err();
+        assert_eq!(err.field(), Some("page"));
+
+        let err = config.deserialize::<Search>("filter=viz").unwrap_err();
+        assert_eq!(err.field(), Some("filter"));
+
+        let err = config.deserialize::<Search>("a[b][c][d]=1").unwrap_err();
+        assert_eq!(err.field(), Some("a[b][c][d]"));
+        assert_eq!(err.message(), "nested too deeply");
+
+        let err = config
+            .deserialize::<Search>("tags=a&tags[x]=b")
+            .unwrap_err();
+        assert_eq!(err.field(), Some("tags[x]"));
+    }
+}
@@ -14,7 +14,7 @@ use serde_json::{from_value, to_value, Value};
 
 use sessions_core::{Data, State, CHANGED, PURGED, RENEWED, UNCHANGED};
 
-use crate::{Error, FromRequest, IntoResponse, Request, RequestExt, StatusCode};
+use crate::{Error, FromRequest, FromRequestParts, IntoResponse, Request, RequestExt, StatusCode};
 
 /// A session for the current request.
 #[derive(Clone)]
@@ -170,11 +170,19 @@ impl fmt::Debug for Session {
     }
 }
 
+impl FromRequestParts for Session {
+    type Error = Infallible;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        Ok(req.session().clone())
+    }
+}
+
 impl FromRequest for Session {
     type Error = Infallible;
 
     async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
-        Ok(req.session().clone())
+        Self::extract_parts(req).await
     }
 }
 
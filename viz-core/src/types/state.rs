@@ -2,12 +2,14 @@
 
 use std::{
     any::type_name,
+    fmt,
     ops::{Deref, DerefMut},
+    sync::{Arc, OnceLock},
 };
 
 use crate::{
-    handler::Transform, Error, FromRequest, Handler, IntoResponse, Request, RequestExt, Response,
-    Result, StatusCode, ThisError,
+    handler::Transform, BoxHandler, Error, FromRequest, FromRequestParts, Handler, IntoResponse,
+    Request, RequestExt, Response, Result, StatusCode, ThisError,
 };
 
 /// Extracts state from the extensions of a request.
@@ -49,6 +51,17 @@ impl<T> DerefMut for State<T> {
     }
 }
 
+impl<T> FromRequestParts for State<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Error = StateError;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        req.state().map(Self).ok_or_else(StateError::new::<T>)
+    }
+}
+
 impl<T> FromRequest for State<T>
 where
     T: Clone + Send + Sync + 'static,
@@ -56,7 +69,7 @@ where
     type Error = StateError;
 
     async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
-        req.state().map(Self).ok_or_else(StateError::new::<T>)
+        Self::extract_parts(req).await
     }
 }
 
@@ -87,6 +100,83 @@ where
     }
 }
 
+/// Gets the sub-state from a reference of the application state, e.g. the database pool or the
+/// config from the `AppState`.
+pub trait FromRef<T> {
+    /// Converts to this type from a reference of the application state.
+    fn from_ref(input: &T) -> Self;
+}
+
+impl<T> FromRef<T> for T
+where
+    T: Clone,
+{
+    fn from_ref(input: &T) -> Self {
+        input.clone()
+    }
+}
+
+/// A cell of the application state, which is filled by the router.
+///
+/// The handlers read the state from the cell directly, no lookup in the extensions of the
+/// request.
+pub struct StateCell<S>(Arc<OnceLock<S>>);
+
+impl<S> StateCell<S> {
+    /// Creates an empty `StateCell`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self(Arc::new(OnceLock::new()))
+    }
+
+    /// Gets the reference of the application state.
+    #[must_use]
+    pub fn get(&self) -> Option<&S> {
+        self.0.get()
+    }
+
+    /// Sets the application state, the cell can only be set once.
+    ///
+    /// # Errors
+    ///
+    /// Returns the state if the cell is already set.
+    pub fn set(&self, state: S) -> Result<(), S> {
+        self.0.set(state)
+    }
+}
+
+impl<S> Clone for StateCell<S> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<S> Default for StateCell<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> fmt::Debug for StateCell<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateCell")
+            .field("state", &type_name::<S>())
+            .field("is_set", &self.0.get().is_some())
+            .finish()
+    }
+}
+
+/// A function handler which takes the [`State`] of a sub-state as the first argument, the
+/// sub-state is got by the [`FromRef`] from the application state `S`.
+///
+/// It's implemented for the functions like the [`FnExt`](crate::FnExt), e.g.
+/// `async fn list_users(State(db): State<Db>, Query(q): Query<Q>) -> Result<Response>`.
+pub trait StateHandler<S, E>: Clone + Send + Sync + 'static {
+    /// Converts self to a [`Handler`] which reads the application state from the cell.
+    #[must_use]
+    fn into_state_handler(self, cell: StateCell<S>) -> BoxHandler;
+}
+
 /// A [`State`] error.
 #[derive(ThisError, Debug)]
 #[error("missing state type `{0}`")]
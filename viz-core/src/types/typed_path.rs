@@ -0,0 +1,69 @@
+//! Represents a typed path.
+
+use std::fmt::{self, Write};
+
+/// A path of a route which is declared on a type, the fields are the parameters of the path.
+///
+/// It can be derived by the `TypedPath` macro, which implements the [`FromRequest`][crate::FromRequest]
+/// too. The pattern is checked against the fields at compile time.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(TypedPath)]
+/// #[typed_path("/users/:id/posts/:post_id")]
+/// struct UserPost {
+///     id: u64,
+///     post_id: String,
+/// }
+///
+/// async fn show(post: UserPost) -> Result<String> {
+///     Ok(post.to_uri())
+/// }
+///
+/// let app = Router::new().typed_route::<UserPost>(get(show.into_handler()));
+/// ```
+pub trait TypedPath: Sized {
+    /// The pattern of the path, e.g. `/users/:id`.
+    const PATH: &'static str;
+
+    /// Generates the URI of the path, the parameters are formatted by the `Display` and
+    /// percent-encoded, the `/`s are only kept in the `:name+` and `:name*` parameters.
+    fn to_uri(&self) -> String;
+}
+
+/// Appends the percent-encoded parameter to the URI, it is used by the `TypedPath` macro.
+#[doc(hidden)]
+pub fn encode_path_param<T>(uri: &mut String, value: &T, catch_all: bool)
+where
+    T: fmt::Display + ?Sized,
+{
+    for b in value.to_string().bytes() {
+        match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'!'
+            | b'$'
+            | b'&'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b'+'
+            | b','
+            | b';'
+            | b'='
+            | b':'
+            | b'@' => uri.push(char::from(b)),
+            b'/' if catch_all => uri.push('/'),
+            _ => {
+                let _ = write!(uri, "%{b:02X}");
+            }
+        }
+    }
+}
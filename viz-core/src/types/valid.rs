@@ -0,0 +1,218 @@
+//! Represents a validation extractor.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+use crate::{
+    header::{HeaderValue, CONTENT_TYPE},
+    Error, FromRequest, FromRequestParts, IntoResponse, Request, Response, Result, StatusCode,
+    ThisError,
+};
+
+/// Extracts the data by the inner extractor, then validates it via [`Validate`].
+///
+/// It works with the extractors which dereference to the data, e.g. `Json<T>`, `Form<T>`,
+/// `Query<T>` and `Params<T>`.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(Deserialize, Validate)]
+/// struct SignUp {
+///     #[validate(length(min = 1, max = 32))]
+///     name: String,
+///     #[validate(range(min = 18))]
+///     age: u8,
+/// }
+///
+/// async fn create(Valid(Json(data)): Valid<Json<SignUp>>) -> Result<()> {
+///     Ok(())
+/// }
+/// ```
+pub struct Valid<E>(pub E);
+
+impl<E> Valid<E> {
+    /// Consumes the Valid, returning the inner extractor.
+    #[inline]
+    pub fn into_inner(self) -> E {
+        self.0
+    }
+}
+
+impl<E> Clone for Valid<E>
+where
+    E: Clone,
+{
+    fn clone(&self) -> Self {
+        Valid(self.0.clone())
+    }
+}
+
+impl<E> AsRef<E> for Valid<E> {
+    fn as_ref(&self) -> &E {
+        &self.0
+    }
+}
+
+impl<E> Deref for Valid<E> {
+    type Target = E;
+
+    fn deref(&self) -> &E {
+        &self.0
+    }
+}
+
+impl<E> DerefMut for Valid<E> {
+    fn deref_mut(&mut self) -> &mut E {
+        &mut self.0
+    }
+}
+
+impl<E> fmt::Debug for Valid<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        E::fmt(self, f)
+    }
+}
+
+impl<E> FromRequest for Valid<E>
+where
+    E: FromRequest + Deref + Send,
+    E::Target: Validate,
+{
+    type Error = ValidError<E::Error>;
+
+    async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+        let data = E::extract(req).await.map_err(ValidError::Extract)?;
+        data.validate().map_err(ValidError::Invalid)?;
+        Ok(Self(data))
+    }
+}
+
+impl<E> FromRequestParts for Valid<E>
+where
+    E: FromRequestParts + Deref + Send,
+    E::Target: Validate,
+{
+    type Error = ValidError<E::Error>;
+
+    async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+        let data = E::extract_parts(req).await.map_err(ValidError::Extract)?;
+        data.validate().map_err(ValidError::Invalid)?;
+        Ok(Self(data))
+    }
+}
+
+/// Rejects with an error when the extraction or the validation fails.
+#[derive(ThisError, Debug)]
+pub enum ValidError<R> {
+    /// Responds the rejection of the inner extractor.
+    #[error("failed to extract data")]
+    Extract(R),
+
+    /// 422
+    #[error("validation failed, {0}")]
+    Invalid(ValidationErrors),
+}
+
+impl<R> ValidError<R> {
+    /// Flattens the validation errors into the field paths and the messages,
+    /// e.g. `address.city` or `items[0].name`, sorted by the paths.
+    #[must_use]
+    pub fn fields(&self) -> Vec<(String, String)> {
+        match self {
+            Self::Extract(_) => Vec::new(),
+            Self::Invalid(errors) => {
+                let mut items = Vec::new();
+                flatten(errors, "", &mut items);
+                items
+                    .into_iter()
+                    .map(|(field, _, message)| (field, message))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl<R> IntoResponse for ValidError<R>
+where
+    R: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        let errors = match self {
+            Self::Extract(r) => return r.into_response(),
+            Self::Invalid(errors) => errors,
+        };
+
+        let mut items = Vec::new();
+        flatten(&errors, "", &mut items);
+
+        let body = serde_json::json!({
+            "message": "validation failed",
+            "errors": items
+                .into_iter()
+                .map(|(field, code, message)| serde_json::json!({
+                    "field": field,
+                    "code": code,
+                    "message": message,
+                }))
+                .collect::<Vec<_>>(),
+        });
+
+        let mut resp = (StatusCode::UNPROCESSABLE_ENTITY, body.to_string()).into_response();
+        resp.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(mime::APPLICATION_JSON.as_ref()),
+        );
+        resp
+    }
+}
+
+impl<R> From<ValidError<R>> for Error
+where
+    R: IntoResponse,
+{
+    fn from(e: ValidError<R>) -> Self {
+        e.into_error()
+    }
+}
+
+/// Collects the `(path, code, message)` of the nested errors.
+fn flatten(errors: &ValidationErrors, prefix: &str, items: &mut Vec<(String, String, String)>) {
+    let mut entries = errors.errors().iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (name, kind) in entries {
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                items.extend(errors.iter().map(|error| {
+                    (
+                        path.clone(),
+                        error.code.to_string(),
+                        error
+                            .message
+                            .as_ref()
+                            .map_or_else(|| error.to_string(), ToString::to_string),
+                    )
+                }));
+            }
+            ValidationErrorsKind::Struct(errors) => flatten(errors, &path, items),
+            ValidationErrorsKind::List(list) => {
+                for (index, errors) in list {
+                    flatten(errors, &format!("{path}[{index}]"), items);
+                }
+            }
+        }
+    }
+}
@@ -30,6 +30,14 @@ async fn handler() -> Result<()> {
         }
     }
 
+    impl FromRequestParts for MyU8 {
+        type Error = std::convert::Infallible;
+
+        async fn extract_parts(_: &Request) -> Result<Self, Self::Error> {
+            Ok(MyU8(u8::MAX))
+        }
+    }
+
     struct MyString(String);
 
     impl FromRequest for MyString {
@@ -40,6 +48,14 @@ async fn handler() -> Result<()> {
         }
     }
 
+    impl FromRequestParts for MyString {
+        type Error = std::convert::Infallible;
+
+        async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+            Ok(MyString(req.uri().path().to_string()))
+        }
+    }
+
     impl From<MyString> for Error {
         fn from(e: MyString) -> Self {
             Error::Responder(Response::new(Full::from(e.0).into()))
@@ -16,3 +16,6 @@ pub mod embed;
 
 #[cfg(feature = "prometheus")]
 pub mod prometheus;
+
+#[cfg(feature = "openapi")]
+pub mod openapi;
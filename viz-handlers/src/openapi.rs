@@ -0,0 +1,142 @@
+//! [Swagger UI] and [Redoc] pages for the `OpenAPI` document.
+//!
+//! The assets of the pinned versions are loaded from the CDN, the document is fetched from the
+//! given URL.
+//!
+//! [Swagger UI]: https://swagger.io/tools/swagger-ui/
+//! [Redoc]: https://redocly.com/redoc
+
+use std::{fmt::Write, sync::Arc};
+
+use viz_core::{Handler, Request, Response, ResponseExt, Result};
+
+/// The version of the `swagger-ui-dist` package.
+const SWAGGER_UI_VERSION: &str = "5.17.14";
+
+/// The version of the `redoc` package.
+const REDOC_VERSION: &str = "2.1.5";
+
+/// Serves the Swagger UI page.
+#[derive(Clone, Debug)]
+pub struct SwaggerUi {
+    html: Arc<str>,
+}
+
+impl SwaggerUi {
+    /// Creates a Swagger UI page for the document URL.
+    #[must_use]
+    pub fn new(url: &str) -> Self {
+        Self {
+            html: format!(
+                r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>Swagger UI</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@{SWAGGER_UI_VERSION}/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@{SWAGGER_UI_VERSION}/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.onload = () => {{
+      window.ui = SwaggerUIBundle({{ url: {url}, dom_id: '#swagger-ui' }});
+    }};
+  </script>
+</body>
+</html>"#,
+                url = js_string(url)
+            )
+            .into(),
+        }
+    }
+}
+
+#[viz_core::async_trait]
+impl Handler<Request> for SwaggerUi {
+    type Output = Result<Response>;
+
+    async fn call(&self, _: Request) -> Self::Output {
+        Ok(Response::html(self.html.to_string()))
+    }
+}
+
+/// Serves the Redoc page.
+#[derive(Clone, Debug)]
+pub struct Redoc {
+    html: Arc<str>,
+}
+
+impl Redoc {
+    /// Creates a Redoc page for the document URL.
+    #[must_use]
+    pub fn new(url: &str) -> Self {
+        Self {
+            html: format!(
+                r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>Redoc</title>
+</head>
+<body>
+  <redoc spec-url={url}></redoc>
+  <script src="https://unpkg.com/redoc@{REDOC_VERSION}/bundles/redoc.standalone.js" crossorigin></script>
+</body>
+</html>"#,
+                url = attr_string(url)
+            )
+            .into(),
+        }
+    }
+}
+
+#[viz_core::async_trait]
+impl Handler<Request> for Redoc {
+    type Output = Result<Response>;
+
+    async fn call(&self, _: Request) -> Self::Output {
+        Ok(Response::html(self.html.to_string()))
+    }
+}
+
+/// Quotes the URL as an HTML attribute value.
+fn attr_string(url: &str) -> String {
+    let mut s = String::with_capacity(url.len() + 2);
+    s.push('"');
+    for c in url.chars() {
+        match c {
+            '"' => s.push_str("&quot;"),
+            '<' => s.push_str("&lt;"),
+            '>' => s.push_str("&gt;"),
+            '&' => s.push_str("&amp;"),
+            _ => s.push(c),
+        }
+    }
+    s.push('"');
+    s
+}
+
+/// Quotes the URL as a JavaScript string literal, the `<`, `>` and `&` are escaped too, so it
+/// can not close the `<script>` element.
+fn js_string(url: &str) -> String {
+    let mut s = String::with_capacity(url.len() + 2);
+    s.push('"');
+    for c in url.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if c.is_control() || matches!(c, '<' | '>' | '&' | '\u{2028}' | '\u{2029}') => {
+                let _ = write!(s, "\\u{:04x}", u32::from(c));
+            }
+            _ => s.push(c),
+        }
+    }
+    s.push('"');
+    s
+}
@@ -0,0 +1,170 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{punctuated::Punctuated, Data, DeriveInput, Error, Fields, Meta, Result, Token, Type};
+
+/// The max size of the tuple extractors.
+const MAX_TUPLE_SIZE: usize = 12;
+
+/// The options of the `#[from_request(...)]`.
+#[derive(Default)]
+struct Attrs {
+    /// The fields are all the parts extractors, generates the `FromRequestParts` too.
+    parts: bool,
+    /// The rejection type, which should implement `From<viz_core::Error>` and `IntoResponse`.
+    rejection: Option<Type>,
+}
+
+impl Attrs {
+    fn parse(ast: &DeriveInput) -> Result<Self> {
+        let mut attrs = Self::default();
+
+        for attr in ast
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("from_request"))
+        {
+            for meta in attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)? {
+                match meta {
+                    Meta::Path(path) if path.is_ident("parts") => attrs.parts = true,
+                    Meta::NameValue(arg) if arg.path.is_ident("rejection") => {
+                        let value = &arg.value;
+                        attrs.rejection = Some(syn::parse2(quote!(#value))?);
+                    }
+                    meta => {
+                        return Err(Error::new_spanned(
+                            meta,
+                            "expected `parts` or `rejection = Type`",
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+pub(crate) fn generate(input: TokenStream) -> Result<TokenStream> {
+    let ast = syn::parse::<DeriveInput>(input)?;
+    let name = &ast.ident;
+    let attrs = Attrs::parse(&ast)?;
+
+    let Data::Struct(data) = &ast.data else {
+        return Err(Error::new_spanned(
+            name,
+            "`FromRequest` can only be derived for structs",
+        ));
+    };
+
+    let types = data
+        .fields
+        .iter()
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+    let bindings = (0..types.len())
+        .map(|n| format_ident!("field{n}"))
+        .collect::<Vec<_>>();
+    let construct = match &data.fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|field| &field.ident);
+            quote!(Self { #(#idents: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(Self(#(#bindings),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let error = attrs
+        .rejection
+        .as_ref()
+        .map_or_else(|| quote!(viz_core::Error), |rejection| quote!(#rejection));
+
+    // The generic fields should be the extractors, the concrete fields are checked by the
+    // tuple extractors.
+    let mut generics = ast.generics.clone();
+    if !generics.params.is_empty() {
+        let predicates = &mut generics.make_where_clause().predicates;
+        for (n, ty) in types.iter().enumerate() {
+            let bound = if attrs.parts || n + 1 < types.len() {
+                quote!(viz_core::FromRequestParts)
+            } else {
+                quote!(viz_core::FromRequest)
+            };
+            predicates.push(syn::parse_quote!(#ty: #bound + ::core::marker::Send));
+            predicates.push(syn::parse_quote!(
+                <#ty as #bound>::Error: viz_core::IntoResponse + ::core::marker::Send
+            ));
+        }
+        if let Some(rejection) = &attrs.rejection {
+            predicates.push(syn::parse_quote!(
+                #rejection: ::core::convert::From<viz_core::Error>
+            ));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let stream = if attrs.parts {
+        let (ty, pat) = group(&types, &bindings);
+        quote! {
+            impl #impl_generics viz_core::FromRequestParts for #name #ty_generics #where_clause {
+                type Error = #error;
+
+                async fn extract_parts(req: &viz_core::Request) -> ::core::result::Result<Self, Self::Error> {
+                    let #pat = <#ty as viz_core::FromRequestParts>::extract_parts(req)
+                        .await?;
+                    Ok(#construct)
+                }
+            }
+
+            impl #impl_generics viz_core::FromRequest for #name #ty_generics #where_clause {
+                type Error = #error;
+
+                async fn extract(req: &mut viz_core::Request) -> ::core::result::Result<Self, Self::Error> {
+                    <Self as viz_core::FromRequestParts>::extract_parts(req).await
+                }
+            }
+        }
+    } else {
+        // Only the last field can consume the body.
+        let body = if let (Some((last_ty, types)), Some((last, bindings))) =
+            (types.split_last(), bindings.split_last())
+        {
+            let (ty, pat) = group(types, bindings);
+            quote! {
+                let (#pat, #last) = <(#ty, #last_ty) as viz_core::FromRequest>::extract(req)
+                    .await?;
+            }
+        } else {
+            quote!(let _ = req;)
+        };
+        quote! {
+            impl #impl_generics viz_core::FromRequest for #name #ty_generics #where_clause {
+                type Error = #error;
+
+                async fn extract(req: &mut viz_core::Request) -> ::core::result::Result<Self, Self::Error> {
+                    #body
+                    Ok(#construct)
+                }
+            }
+        }
+    };
+
+    Ok(stream.into())
+}
+
+/// Groups the parts extractors into the tuples, the tuples are nested if there are too many.
+pub(crate) fn group(
+    types: &[&Type],
+    bindings: &[syn::Ident],
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    if types.len() <= MAX_TUPLE_SIZE {
+        return (quote!((#(#types,)*)), quote!((#(#bindings,)*)));
+    }
+
+    let size = types.len().div_ceil(MAX_TUPLE_SIZE);
+    let (types, bindings): (Vec<_>, Vec<_>) = types
+        .chunks(size)
+        .zip(bindings.chunks(size))
+        .map(|(types, bindings)| group(types, bindings))
+        .unzip();
+    (quote!((#(#types,)*)), quote!((#(#bindings,)*)))
+}
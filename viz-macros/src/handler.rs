@@ -0,0 +1,395 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    punctuated::Punctuated, Expr, ExprLit, FnArg, GenericParam, Generics, ItemFn, Lit, LitStr,
+    MetaNameValue, Result, ReturnType, Token, Type,
+};
+
+use crate::from_request::group;
+
+/// The middleware of the handler, `#[handler(with = Auth, limits = "json:1mb")]`.
+#[derive(Default)]
+pub(crate) struct Attrs {
+    transforms: Vec<proc_macro2::TokenStream>,
+}
+
+impl Attrs {
+    /// Parses the attribute args of the `#[handler(...)]`.
+    pub(crate) fn parse(args: TokenStream) -> Result<Self> {
+        Self::new(syn::parse::Parser::parse(
+            Punctuated::<MetaNameValue, Token![,]>::parse_terminated,
+            args,
+        )?)
+    }
+
+    pub(crate) fn new(args: Punctuated<MetaNameValue, Token![,]>) -> Result<Self> {
+        let mut attrs = Self::default();
+
+        for arg in args {
+            if arg.path.is_ident("with") {
+                let t = arg.value;
+                attrs.transforms.push(quote!(#t));
+            } else if arg.path.is_ident("limits") {
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Str(limits),
+                    ..
+                }) = &arg.value
+                else {
+                    return Err(syn::Error::new_spanned(
+                        &arg.value,
+                        "expected the limits, e.g. `\"json:1mb, form:64kb\"`",
+                    ));
+                };
+                let limits = parse_limits(limits)?
+                    .into_iter()
+                    .map(|(name, limit)| quote!(.set(#name, #limit)));
+                attrs.transforms.push(quote! {
+                    viz_core::middleware::limits::Config::new()
+                        .limits(viz_core::types::Limits::default()#(#limits)*)
+                });
+            } else {
+                return Err(syn::Error::new_spanned(
+                    &arg.path,
+                    "expected `with` or `limits`",
+                ));
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+pub(crate) fn generate(attrs: &Attrs, input: TokenStream) -> Result<TokenStream> {
+    let ast = syn::parse::<ItemFn>(input)?;
+
+    if let Some(FnArg::Receiver(receiver)) = ast.sig.inputs.first() {
+        if receiver.mutability.is_some() || receiver.colon_token.is_some() {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "expected `&self` or `self` of a handler method",
+            ));
+        }
+        return Ok(generate_method(attrs, &ast));
+    }
+
+    let vis = &ast.vis;
+    let docs = ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .cloned()
+        .collect::<Vec<_>>();
+    let name = ast.sig.ident.clone();
+    let asyncness = asyncness(&ast);
+    let out = output(&ast);
+    let (extract, args) = extractors(&ast);
+
+    let generics = generics(&ast);
+    let types = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let decls = generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Const(c) => {
+                let ident = &c.ident;
+                let ty = &c.ty;
+                quote!(const #ident: #ty)
+            }
+            GenericParam::Type(ty) => {
+                let ident = &ty.ident;
+                quote!(#ident)
+            }
+            GenericParam::Lifetime(_) => quote!(),
+        })
+        .collect::<Vec<_>>();
+    let is_generic = !generics.params.is_empty();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let turbofish = ty_generics.as_turbofish();
+
+    let transforms = &attrs.transforms;
+    // The middleware is built once, the generic handler keeps it in a field.
+    let handle = quote! {
+        async fn handle #impl_generics (
+            mut req: viz_core::Request,
+        ) -> viz_core::Result<viz_core::Response> #where_clause {
+            #extract
+            let res = #name #turbofish(#(#args),*)#asyncness;
+            #out.map(viz_core::IntoResponse::into_response)
+        }
+    };
+    let build = quote! {{
+        let handler = handle #turbofish;
+        #(let handler = viz_core::HandlerExt::with(handler, #transforms);)*
+        viz_core::HandlerExt::boxed(handler)
+    }};
+
+    let (def, body) = if is_generic {
+        let def = quote! {
+            #(#docs)*
+            #[allow(non_camel_case_types)]
+            #vis struct #name<#(#decls),*>(
+                ::core::marker::PhantomData<fn() -> (#(#types,)*)>,
+                viz_core::BoxHandler,
+            );
+
+            impl #impl_generics ::core::default::Default for #name #ty_generics #where_clause {
+                #[allow(unused, unused_mut)]
+                fn default() -> Self {
+                    #ast
+                    #handle
+                    Self(::core::marker::PhantomData, #build)
+                }
+            }
+
+            impl #impl_generics ::core::clone::Clone for #name #ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    Self(::core::marker::PhantomData, ::core::clone::Clone::clone(&self.1))
+                }
+            }
+        };
+        let body = quote! {
+            viz_core::Handler::call(&self.1, req).await
+        };
+        (def, body)
+    } else {
+        let def = quote! {
+            #(#docs)*
+            #[allow(non_camel_case_types)]
+            #[derive(Clone)]
+            #vis struct #name;
+        };
+        let body = if transforms.is_empty() {
+            quote! {
+                #ast
+                #extract
+                let res = #name(#(#args),*)#asyncness;
+                #out.map(viz_core::IntoResponse::into_response)
+            }
+        } else {
+            quote! {
+                #ast
+                #handle
+                static HANDLER: ::std::sync::OnceLock<viz_core::BoxHandler> =
+                    ::std::sync::OnceLock::new();
+                let handler = HANDLER.get_or_init(|| #build);
+                viz_core::Handler::call(handler, req).await
+            }
+        };
+        (def, body)
+    };
+
+    let stream = quote! {
+        #def
+
+        #[viz_core::async_trait]
+        impl #impl_generics viz_core::Handler<viz_core::Request> for #name #ty_generics
+        #where_clause
+        {
+            type Output = viz_core::Result<viz_core::Response>;
+
+            #[allow(unused, unused_mut)]
+            async fn call(&self, mut req: viz_core::Request) -> Self::Output {
+                #body
+            }
+        }
+    };
+
+    Ok(stream.into())
+}
+
+/// Generates a method which returns the handler of the `&self` or `self` method, the `Self`
+/// should be cloneable.
+fn generate_method(attrs: &Attrs, ast: &ItemFn) -> TokenStream {
+    let vis = &ast.vis;
+    let name = &ast.sig.ident;
+    let handler = syn::Ident::new(&format!("{name}_handler"), name.span());
+    let asyncness = asyncness(ast);
+    let out = output(ast);
+    let (extract, args) = extractors(ast);
+    let transforms = &attrs.transforms;
+    let doc = format!(" Returns the handler of the [`Self::{name}`].");
+
+    let stream = quote! {
+        #ast
+
+        #[doc = #doc]
+        #[allow(unused, unused_mut)]
+        #vis fn #handler(
+            &self,
+        ) -> impl viz_core::Handler<
+            viz_core::Request,
+            Output = viz_core::Result<viz_core::Response>,
+        > + ::core::clone::Clone
+        where
+            Self: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static,
+        {
+            let this = ::core::clone::Clone::clone(self);
+            let handler = move |mut req: viz_core::Request| {
+                let this = ::core::clone::Clone::clone(&this);
+                async move {
+                    #extract
+                    let res = this.#name(#(#args),*)#asyncness;
+                    let out: viz_core::Result<viz_core::Response> =
+                        #out.map(viz_core::IntoResponse::into_response);
+                    out
+                }
+            };
+            #(let handler = viz_core::HandlerExt::with(handler, #transforms);)*
+            handler
+        }
+    };
+
+    stream.into()
+}
+
+/// The generics of the generated handler, the lifetimes are late bound, so only the types and
+/// the consts are the parameters, and the types should be `'static`.
+pub(crate) fn generics(ast: &ItemFn) -> Generics {
+    let mut generics = ast.sig.generics.clone();
+    generics.params = generics
+        .params
+        .into_iter()
+        .filter(|param| !matches!(param, GenericParam::Lifetime(_)))
+        .collect();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!('static));
+    }
+    generics
+}
+
+fn asyncness(ast: &ItemFn) -> Option<proc_macro2::TokenStream> {
+    ast.sig.asyncness.map(|_| quote!(.await))
+}
+
+/// The output of the handler, the plain types are wrapped in `Ok`.
+fn output(ast: &ItemFn) -> proc_macro2::TokenStream {
+    let mut out = quote!(Ok(res));
+    let mut is_ok_type = false;
+    match &ast.sig.output {
+        // ()
+        ReturnType::Default => {
+            is_ok_type = true;
+        }
+        ReturnType::Type(_, ty) => match &**ty {
+            syn::Type::Path(path) => {
+                if let Some(seg) = &path.path.segments.first() {
+                    is_ok_type = true;
+                    // T
+                    // impl IntoResponse
+                    // Result<T>
+                    // Result<impl IntoResponse>
+                    if seg.ident == "Result" {
+                        out = quote!(res);
+                    }
+                }
+            }
+            syn::Type::ImplTrait(i) => {
+                if let Some(syn::TypeParamBound::Trait(d)) = &i.bounds.first() {
+                    // T
+                    // impl IntoResponse
+                    if matches!(d.path.get_ident(), Some(ident) if ident == "IntoResponse") {
+                        is_ok_type = true;
+                    }
+                }
+            }
+            syn::Type::Tuple(_) => {
+                // (T,...)
+                is_ok_type = true;
+            }
+            _ => {
+                is_ok_type = false;
+            }
+        },
+    }
+
+    if !is_ok_type {
+        out = quote!();
+    }
+
+    out
+}
+
+/// Extracts the arguments like the tuple extractors, only the last argument can consume the
+/// body, the others are the parts extractors, and the remaining `Request` can be the last one.
+fn extractors(ast: &ItemFn) -> (proc_macro2::TokenStream, Vec<syn::Ident>) {
+    let types = ast
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(pat) => Some(&*pat.ty),
+            FnArg::Receiver(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let bindings = (0..types.len())
+        .map(|n| format_ident!("arg{n}"))
+        .collect::<Vec<_>>();
+
+    let (Some((last_ty, types)), Some((last, parts))) = (types.split_last(), bindings.split_last())
+    else {
+        return (quote!(), bindings);
+    };
+    let (ty, pat) = group(types, parts);
+    let extract = if is_request(last_ty) {
+        quote! {
+            let #pat = <#ty as viz_core::FromRequestParts>::extract_parts(&req).await?;
+            let #last = req;
+        }
+    } else {
+        quote! {
+            let (#pat, #last) = <(#ty, #last_ty) as viz_core::FromRequest>::extract(&mut req)
+                .await?;
+        }
+    };
+
+    (extract, bindings)
+}
+
+/// The remaining `Request` is passed as the last argument.
+fn is_request(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Path(path) if path.qself.is_none()
+            && path.path.segments.last().is_some_and(|seg| {
+                seg.ident == "Request" && seg.arguments.is_none()
+            })
+    )
+}
+
+/// Parses the limits, e.g. `json:1mb, form:64kb`, the units are `b`, `kb`, `mb` and `gb`.
+fn parse_limits(limits: &LitStr) -> Result<Vec<(String, u64)>> {
+    let err = || {
+        syn::Error::new_spanned(
+            limits,
+            "expected the limits, e.g. `\"json:1mb, form:64kb\"`",
+        )
+    };
+
+    limits
+        .value()
+        .split(',')
+        .map(|limit| {
+            let (name, size) = limit.split_once(':').ok_or_else(err)?;
+            let size = size.trim().to_ascii_lowercase();
+            let digits = size
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(size.len());
+            let (num, unit) = size.split_at(digits);
+            let num = num.parse::<u64>().map_err(|_| err())?;
+            let unit = match unit.trim() {
+                "" | "b" => 1,
+                "kb" => 1024,
+                "mb" => 1024 * 1024,
+                "gb" => 1024 * 1024 * 1024,
+                _ => return Err(err()),
+            };
+            let limit = num
+                .checked_mul(unit)
+                .ok_or_else(|| syn::Error::new_spanned(limits, "limit is too large"))?;
+            Ok((name.trim().to_string(), limit))
+        })
+        .collect()
+}
@@ -0,0 +1,267 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    punctuated::Punctuated, Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields, Lit,
+    LitStr, Result, Token,
+};
+
+/// The body of the response.
+enum Body {
+    /// Responds with the field as the JSON data, `#[json]`.
+    Json,
+    /// Responds with the field or the formatted fields as the text, `#[text]` or
+    /// `#[text("{id} not found")]`.
+    Text(Option<LitStr>),
+}
+
+/// The attributes of the struct, the enum or the variant.
+#[derive(Default)]
+struct Attrs {
+    status: Option<proc_macro2::TokenStream>,
+    headers: Vec<proc_macro2::TokenStream>,
+    body: Option<Body>,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut this = Self::default();
+
+        for attr in attrs {
+            if attr.path().is_ident("status") {
+                this.status = Some(status(&attr.parse_args::<Expr>()?)?);
+            } else if attr.path().is_ident("header") {
+                this.headers.push(header(attr)?);
+            } else if attr.path().is_ident("json") {
+                attr.meta.require_path_only()?;
+                this.body = Some(Body::Json);
+            } else if attr.path().is_ident("text") {
+                this.body = Some(Body::Text(match &attr.meta {
+                    syn::Meta::Path(_) => None,
+                    _ => Some(attr.parse_args::<LitStr>()?),
+                }));
+            }
+        }
+
+        Ok(this)
+    }
+}
+
+pub(crate) fn generate(input: TokenStream) -> Result<TokenStream> {
+    let ast = syn::parse::<DeriveInput>(input)?;
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let attrs = Attrs::parse(&ast.attrs)?;
+
+    let arms = match &ast.data {
+        Data::Struct(data) => vec![arm(&quote!(Self), &data.fields, &Attrs::default(), &attrs)?],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                arm(
+                    &quote!(Self::#ident),
+                    &variant.fields,
+                    &attrs,
+                    &Attrs::parse(&variant.attrs)?,
+                )
+            })
+            .collect::<Result<_>>()?,
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                name,
+                "`IntoResponse` can not be derived for unions",
+            ))
+        }
+    };
+
+    let stream = quote! {
+        impl #impl_generics viz_core::IntoResponse for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn into_response(self) -> viz_core::Response {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for viz_core::Error
+        #where_clause
+        {
+            fn from(e: #name #ty_generics) -> Self {
+                viz_core::IntoResponse::into_error(e)
+            }
+        }
+    };
+
+    Ok(stream.into())
+}
+
+/// Generates the arm of the `match self`, the status and the headers of the variant are
+/// merged with the ones of the enum, and the body of the enum is the default of the variants,
+/// the `#[json]` and `#[text]` of the enum are skipped by the unit variants.
+fn arm(
+    path: &proc_macro2::TokenStream,
+    fields: &Fields,
+    parent: &Attrs,
+    attrs: &Attrs,
+) -> Result<proc_macro2::TokenStream> {
+    let bindings = fields
+        .iter()
+        .enumerate()
+        .map(|(n, field)| field.ident.clone().unwrap_or_else(|| format_ident!("f{n}")))
+        .collect::<Vec<_>>();
+    let pat = match fields {
+        Fields::Named(_) => quote!(#path { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+        Fields::Unit => quote!(#path),
+    };
+
+    let body = attrs.body.as_ref().or_else(|| {
+        parent
+            .body
+            .as_ref()
+            .filter(|body| !bindings.is_empty() || matches!(body, Body::Text(Some(_))))
+    });
+
+    let body = match (body, bindings.as_slice()) {
+        (Some(Body::Json), [field]) => {
+            quote!(viz_core::IntoResponse::into_response(viz_core::types::Json(#field)))
+        }
+        (Some(Body::Text(None)), [field]) => {
+            quote!(<viz_core::Response as viz_core::ResponseExt>::text(#field.to_string()))
+        }
+        (Some(Body::Text(Some(format))), _) => {
+            // `{0}` is captured as `{f0}` of the tuple fields.
+            let format = if matches!(fields, Fields::Unnamed(_)) {
+                LitStr::new(&positional(&format.value()), format.span())
+            } else {
+                format.clone()
+            };
+            quote!(<viz_core::Response as viz_core::ResponseExt>::text(
+                ::std::format!(#format)
+            ))
+        }
+        (None, []) => quote!(viz_core::IntoResponse::into_response(())),
+        (None, [field]) => quote!(viz_core::IntoResponse::into_response(#field)),
+        (Some(Body::Json | Body::Text(None)), _) => {
+            return Err(Error::new_spanned(
+                pat,
+                "`#[json]` and `#[text]` expect a single field",
+            ))
+        }
+        (None, _) => {
+            return Err(Error::new_spanned(
+                pat,
+                "expected `#[json]` or `#[text(\"...\")]` for the fields",
+            ))
+        }
+    };
+
+    let status = attrs
+        .status
+        .as_ref()
+        .or(parent.status.as_ref())
+        .map(|status| quote!(*res.status_mut() = #status;));
+    let headers = parent.headers.iter().chain(&attrs.headers);
+
+    Ok(quote! {
+        #pat => {
+            let mut res = #body;
+            #status
+            #(res.headers_mut().insert(#headers);)*
+            res
+        }
+    })
+}
+
+/// The status code, `#[status(404)]` or `#[status(StatusCode::NOT_FOUND)]`.
+fn status(expr: &Expr) -> Result<proc_macro2::TokenStream> {
+    let Expr::Lit(ExprLit {
+        lit: Lit::Int(code),
+        ..
+    }) = expr
+    else {
+        return Ok(quote!(#expr));
+    };
+
+    match code.base10_parse::<u16>()? {
+        code @ 100..=999 => Ok(quote! {
+            viz_core::StatusCode::from_u16(#code)
+                .unwrap_or(viz_core::StatusCode::INTERNAL_SERVER_ERROR)
+        }),
+        _ => Err(Error::new_spanned(
+            code,
+            "expected a status code in 100..=999",
+        )),
+    }
+}
+
+/// The header, `#[header("cache-control", "no-cache")]` or
+/// `#[header(header::CACHE_CONTROL, "no-cache")]`.
+fn header(attr: &Attribute) -> Result<proc_macro2::TokenStream> {
+    let args = attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+    let [name, value] = args.iter().collect::<Vec<_>>()[..] else {
+        return Err(Error::new_spanned(
+            attr,
+            "expected `#[header(\"name\", \"value\")]`",
+        ));
+    };
+
+    let name = match name {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(name),
+            ..
+        }) => {
+            if !is_header_name(&name.value()) {
+                return Err(Error::new_spanned(name, "expected a lowercase header name"));
+            }
+            quote!(viz_core::header::HeaderName::from_static(#name))
+        }
+        name => quote!(#name),
+    };
+
+    let value = match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(value),
+            ..
+        }) => {
+            if !value
+                .value()
+                .bytes()
+                .all(|b| b == b'\t' || (b' '..=b'~').contains(&b))
+            {
+                return Err(Error::new_spanned(value, "expected a visible ASCII value"));
+            }
+            quote!(viz_core::header::HeaderValue::from_static(#value))
+        }
+        value => quote!(#value),
+    };
+
+    Ok(quote!(#name, #value))
+}
+
+/// Checks the lowercase header name, which is used by the `HeaderName::from_static`.
+pub(crate) fn is_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.bytes().all(|b| {
+            b.is_ascii_lowercase() || b.is_ascii_digit() || b"!#$%&'*+-.^_`|~".contains(&b)
+        })
+}
+
+/// Replaces the positional arguments `{0}` with the bindings `{f0}`.
+fn positional(format: &str) -> String {
+    let mut out = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c == '{' {
+            if chars.peek() == Some(&'{') {
+                out.extend(chars.next());
+            } else if chars.peek().is_some_and(char::is_ascii_digit) {
+                out.push('f');
+            }
+        }
+    }
+    out
+}
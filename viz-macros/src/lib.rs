@@ -24,6 +24,223 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! The arguments are extracted like the tuple extractors, only the last argument can consume the
+//! body, the others must be the parts extractors, and the remaining `Request` can be the last
+//! argument.
+//!
+//! ```
+//! # use viz_core::{types::{Json, Params}, Request, Result};
+//! # use viz_macros::handler;
+//!
+//! #[handler]
+//! async fn create_post(Params(id): Params<u64>, Json(title): Json<String>) -> String {
+//!     format!("{id}: {title}")
+//! }
+//!
+//! #[handler]
+//! async fn upload(Params(id): Params<u64>, req: Request) -> Result<String> {
+//!     Ok(format!("{id}: {:?}", req.headers()))
+//! }
+//! ```
+//!
+//! The body can not be consumed before the other extractors.
+//!
+//! ```compile_fail
+//! # use viz_core::types::{Json, Params};
+//! # use viz_macros::handler;
+//!
+//! #[handler]
+//! async fn create_post(Json(title): Json<String>, Params(id): Params<u64>) -> String {
+//!     format!("{id}: {title}")
+//! }
+//! ```
+//!
+//! The generic functions generate the generic handlers, e.g. `list_users::<Db>::default()`.
+//! And the methods with `&self` or `self` generate the methods which return the handlers,
+//! e.g. `users.show_handler()`, the `Self` should be cloneable.
+//!
+//! ```
+//! # use viz_core::{types::{Params, State}, Result};
+//! # use viz_macros::handler;
+//!
+//! #[derive(Clone)]
+//! struct Users {
+//!     prefix: String,
+//! }
+//!
+//! impl Users {
+//!     #[handler(with = State::new(1u8), limits = "json:1mb")]
+//!     async fn show(&self, Params(id): Params<u64>, State(n): State<u8>) -> Result<String> {
+//!         Ok(format!("{}: {id} {n}", self.prefix))
+//!     }
+//! }
+//!
+//! let users = Users { prefix: "users".to_string() };
+//! let handler = users.show_handler();
+//! ```
+//!
+//! # Routes
+//!
+//! The `#[get]`, `#[post]`, `#[put]`, `#[delete]`, `#[head]`, `#[options]`, `#[connect]`,
+//! `#[patch]` and `#[trace]` generate the handler like the `#[handler]`, and record the path and
+//! the HTTP method of the route.
+//!
+//! ## Example
+//!
+//! ```
+//! # use viz_core::{types::Params, Endpoint, Method, Result};
+//! # use viz_macros::{get, post};
+//!
+//! #[get("/users/:id")]
+//! async fn show_user(Params(id): Params<u64>) -> String {
+//!     format!("user {id}")
+//! }
+//!
+//! #[post("/users")]
+//! async fn create_user() -> Result<&'static str> {
+//!     Ok("created")
+//! }
+//!
+//! assert_eq!(show_user::PATH, "/users/:id");
+//! assert_eq!(create_user::METHOD, Method::POST);
+//! ```
+//!
+//! Then mounts them on the router in one call, `Router::new().mount(routes![show_user, create_user])`.
+//!
+//! # `TypedPath`
+//!
+//! Declares the pattern of a route on a struct, the parameters of the pattern must match the
+//! fields of the struct, and the optional parameters, `:name?`, must be the `Option`s.
+//!
+//! The helper attribute is `#[typed_path]`, not `#[path]`, since `path` is the built-in attribute
+//! for the file of a module, a derive helper with the same name is ambiguous.
+//!
+//! The `to_uri` percent-encodes the parameters, the `/`s are kept only in the catch-all
+//! parameters, `:name+` and `:name*`.
+//!
+//! ## Example
+//!
+//! ```
+//! # use viz_core::types::TypedPath;
+//! # use viz_macros::TypedPath;
+//!
+//! #[derive(TypedPath)]
+//! #[typed_path("/users/:id/posts/:post_id")]
+//! struct UserPost {
+//!     id: u64,
+//!     post_id: String,
+//! }
+//!
+//! let post = UserPost { id: 1, post_id: "viz".to_string() };
+//! assert_eq!(post.to_uri(), "/users/1/posts/viz");
+//! ```
+//!
+//! # `FromRequest`
+//!
+//! Extracts a struct whose fields are the extractors, the fields are extracted like the tuple
+//! extractors, so only the last field can consume the body, the others must be the parts
+//! extractors.
+//!
+//! The `#[from_request(parts)]` derives the `FromRequestParts` too if all the fields are the
+//! parts extractors, and the rejection can be mapped by `#[from_request(rejection = Type)]`,
+//! the `Type` should implement the `From<viz_core::Error>` and the `IntoResponse`.
+//!
+//! ## Example
+//!
+//! ```
+//! # use viz_core::{
+//! #     headers::{authorization::Bearer, Authorization},
+//! #     types::{Header, Json, Session, State},
+//! #     Error, IntoResponse, Response,
+//! # };
+//! # use viz_macros::FromRequest;
+//!
+//! #[derive(Clone)]
+//! struct Db;
+//!
+//! struct Rejection(Error);
+//!
+//! impl From<Error> for Rejection {
+//!     fn from(e: Error) -> Self {
+//!         Self(e)
+//!     }
+//! }
+//!
+//! impl IntoResponse for Rejection {
+//!     fn into_response(self) -> Response {
+//!         self.0.into_response()
+//!     }
+//! }
+//!
+//! #[derive(FromRequest)]
+//! #[from_request(rejection = Rejection)]
+//! struct CreatePost {
+//!     db: State<Db>,
+//!     auth: Header<Authorization<Bearer>>,
+//!     session: Option<Session>,
+//!     tags: Json<Vec<String>>,
+//! }
+//! ```
+//!
+//! # `IntoResponse`
+//!
+//! Responds with a struct or an enum, the `#[status(404)]` sets the status code, the
+//! `#[header("name", "value")]` inserts a header, and the body of a single field is responded
+//! as the JSON by `#[json]`, as the text by `#[text]` or the formatted text by
+//! `#[text("{id} not found")]`, otherwise the field itself is responded. The attributes of the
+//! enum apply to all the variants, the variants can override them, and the unit variants skip
+//! the `#[json]` and `#[text]` of the enum.
+//!
+//! The `From<T> for viz_core::Error` is derived too, so the `?` works in the handlers.
+//!
+//! ## Example
+//!
+//! ```
+//! # use viz_core::{header::CACHE_CONTROL, Result, StatusCode};
+//! # use viz_macros::IntoResponse;
+//!
+//! #[derive(IntoResponse)]
+//! #[header("x-service", "users")]
+//! enum UserError {
+//!     #[status(404)]
+//!     #[text("user {id} not found")]
+//!     NotFound { id: u64 },
+//!     #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+//!     #[header(CACHE_CONTROL, "no-cache")]
+//!     #[json]
+//!     Invalid(Vec<String>),
+//! }
+//!
+//! async fn show_user(id: u64) -> Result<String> {
+//!     Err(UserError::NotFound { id })?
+//! }
+//! ```
+//!
+//! # `TypedHeader`
+//!
+//! Declares a custom header on a newtype, the field is decoded by the `FromStr` and encoded by
+//! the `Display`, so it works with the `Header`, the `Headers` and the `TypedHeaders`.
+//!
+//! The `Display` output must be a valid header value, e.g. no newlines, otherwise the header is
+//! skipped when it is encoded, and it panics in debug builds.
+//!
+//! ## Example
+//!
+//! ```
+//! # use viz_core::{headers::ContentType, types::{Header, Headers, TypedHeaders}, IntoResponse};
+//! # use viz_macros::TypedHeader;
+//!
+//! #[derive(TypedHeader)]
+//! #[typed_header("x-tenant-id")]
+//! struct TenantId(u64);
+//!
+//! async fn show_tenant(Header(TenantId(id)): Header<TenantId>) -> impl IntoResponse {
+//!     TypedHeaders((TenantId(id),), format!("tenant {id}"))
+//! }
+//!
+//! async fn create_post(Headers((tenant, ty)): Headers<(TenantId, ContentType)>) {}
+//! ```
 
 #![doc(html_logo_url = "https://viz.rs/logo.svg")]
 #![doc(html_favicon_url = "https://viz.rs/logo.svg")]
@@ -35,105 +252,116 @@
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{FnArg, ItemFn, Result, ReturnType};
+use syn::{parse::ParseStream, punctuated::Punctuated, FnArg, ItemFn, LitStr, Result, Token};
+
+mod from_request;
+mod handler;
+mod into_response;
+mod typed_header;
+mod typed_path;
 
 /// Transforms `extract-handler` to a Handler instance.
+///
+/// The handler can be wrapped by the middleware, e.g.
+/// `#[handler(with = Auth, limits = "json:1mb, form:64kb")]`.
+///
+/// The middleware is built once, on the first call, or by the `Default` of a generic handler.
+///
+/// The limits are checked at compile time.
+///
+/// ```compile_fail
+/// # use viz_macros::handler;
+/// #[handler(limits = "json:99999999999999gb")]
+/// async fn create() {}
+/// ```
 #[proc_macro_attribute]
-pub fn handler(_args: TokenStream, input: TokenStream) -> TokenStream {
-    generate_handler(input).unwrap_or_else(|e| e.to_compile_error().into())
+pub fn handler(args: TokenStream, input: TokenStream) -> TokenStream {
+    handler::Attrs::parse(args)
+        .and_then(|attrs| handler::generate(&attrs, input))
+        .unwrap_or_else(|e| e.to_compile_error().into())
 }
 
-fn generate_handler(input: TokenStream) -> Result<TokenStream> {
-    let ast = syn::parse::<ItemFn>(input)?;
-    let vis = &ast.vis;
-    let docs = ast
-        .attrs
-        .iter()
-        .filter(|attr| attr.path().is_ident("doc"))
-        .cloned()
-        .collect::<Vec<_>>();
-    let name = ast.sig.ident.clone();
-    let asyncness = if ast.sig.asyncness.is_some() {
-        Some(quote!(.await))
-    } else {
-        None
-    };
-    let mut out = quote!(Ok(res));
-    let mut is_ok_type = false;
-    match &ast.sig.output {
-        // ()
-        ReturnType::Default => {
-            is_ok_type = true;
+macro_rules! export_route {
+    ($name:ident $verb:ident) => {
+        #[doc = concat!(" Transforms `extract-handler` to an Endpoint instance with a path and HTTP `", stringify!($verb), "` verb pair.")]
+        #[proc_macro_attribute]
+        pub fn $name(args: TokenStream, input: TokenStream) -> TokenStream {
+            generate_route(stringify!($verb), args, input)
+                .unwrap_or_else(|e| e.to_compile_error().into())
         }
-        ReturnType::Type(_, ty) => match &**ty {
-            syn::Type::Path(path) => {
-                if let Some(seg) = &path.path.segments.first() {
-                    is_ok_type = true;
-                    // T
-                    // impl IntoResponse
-                    // Result<T>
-                    // Result<impl IntoResponse>
-                    if seg.ident == "Result" {
-                        out = quote!(res);
-                    }
-                }
-            }
-            syn::Type::ImplTrait(i) => {
-                if let Some(syn::TypeParamBound::Trait(d)) = &i.bounds.first() {
-                    // T
-                    // impl IntoResponse
-                    if matches!(d.path.get_ident(), Some(ident) if ident == "IntoResponse") {
-                        is_ok_type = true;
-                    }
-                }
-            }
-            syn::Type::Tuple(_) => {
-                // (T,...)
-                is_ok_type = true;
-            }
-            _ => {
-                is_ok_type = false;
+    };
+}
+
+export_route!(get GET);
+export_route!(post POST);
+export_route!(put PUT);
+export_route!(delete DELETE);
+export_route!(head HEAD);
+export_route!(options OPTIONS);
+export_route!(connect CONNECT);
+export_route!(patch PATCH);
+export_route!(trace TRACE);
+
+fn generate_route(verb: &str, args: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    let (path, args) = syn::parse::Parser::parse(
+        |input: ParseStream<'_>| {
+            let path = input.parse::<LitStr>()?;
+            if input.is_empty() {
+                return Ok((path, Punctuated::new()));
             }
+            input.parse::<Token![,]>()?;
+            Ok((path, Punctuated::parse_terminated(input)?))
         },
+        args,
+    )?;
+    let verb = syn::Ident::new(verb, path.span());
+    let ast = syn::parse::<ItemFn>(input.clone())?;
+    if let Some(FnArg::Receiver(receiver)) = ast.sig.inputs.first() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "the route macros do not support methods, use `#[handler]` instead",
+        ));
     }
+    let name = &ast.sig.ident;
+    let generics = handler::generics(&ast);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut stream = handler::generate(&handler::Attrs::new(args)?, input)?;
 
-    if !is_ok_type {
-        out = quote!();
-    }
+    stream.extend(TokenStream::from(quote! {
+        impl #impl_generics viz_core::Endpoint for #name #ty_generics #where_clause {
+            const PATH: &'static str = #path;
+            const METHOD: viz_core::Method = viz_core::Method::#verb;
+        }
+    }));
 
-    let extractors =
-        ast.sig
-            .inputs
-            .clone()
-            .into_iter()
-            .fold(Vec::new(), |mut extractors, input| {
-                if let FnArg::Typed(pat) = input {
-                    let ty = &pat.ty;
-                    extractors
-                        .push(quote!(<#ty as viz_core::FromRequest>::extract(&mut req).await?));
-                }
-                extractors
-            });
+    Ok(stream)
+}
 
-    let stream = quote! {
-        #(#docs)*
-        #[allow(non_camel_case_types)]
-        #[derive(Clone)]
-        #vis struct #name;
+/// Derives the `TypedPath` and the `FromRequest` for a struct with the `#[typed_path("...")]`.
+#[proc_macro_derive(TypedPath, attributes(typed_path))]
+pub fn derive_typed_path(input: TokenStream) -> TokenStream {
+    typed_path::generate(input).unwrap_or_else(|e| e.to_compile_error().into())
+}
 
-        #[viz_core::async_trait]
-        impl viz_core::Handler<viz_core::Request> for #name
-        {
-            type Output = viz_core::Result<viz_core::Response>;
+/// Derives the `FromRequest` for a struct whose fields are the extractors.
+///
+/// The `#[from_request(parts)]` derives the `FromRequestParts` too, and the
+/// `#[from_request(rejection = Type)]` maps the rejection to the `Type`.
+#[proc_macro_derive(FromRequest, attributes(from_request))]
+pub fn derive_from_request(input: TokenStream) -> TokenStream {
+    from_request::generate(input).unwrap_or_else(|e| e.to_compile_error().into())
+}
 
-            #[allow(unused, unused_mut)]
-            async fn call(&self, mut req: viz_core::Request) -> Self::Output {
-                #ast
-                let res = #name(#(#extractors),*)#asyncness;
-                #out.map(viz_core::IntoResponse::into_response)
-            }
-        }
-    };
+/// Derives the `IntoResponse` and the `From<T> for viz_core::Error` for a struct or an enum,
+/// with the `#[status(...)]`, the `#[header(...)]` and the `#[json]` or `#[text]` attributes.
+#[proc_macro_derive(IntoResponse, attributes(status, header, json, text))]
+pub fn derive_into_response(input: TokenStream) -> TokenStream {
+    into_response::generate(input).unwrap_or_else(|e| e.to_compile_error().into())
+}
 
-    Ok(stream.into())
+/// Derives the `headers::Header` for a newtype with the `#[typed_header("...")]`, the field is
+/// decoded by the `FromStr` and encoded by the `Display`.
+#[proc_macro_derive(TypedHeader, attributes(typed_header))]
+pub fn derive_typed_header(input: TokenStream) -> TokenStream {
+    typed_header::generate(input).unwrap_or_else(|e| e.to_compile_error().into())
 }
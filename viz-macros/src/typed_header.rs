@@ -0,0 +1,91 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, Index, LitStr, Result};
+
+use crate::into_response::is_header_name;
+
+pub(crate) fn generate(input: TokenStream) -> Result<TokenStream> {
+    let ast = syn::parse::<DeriveInput>(input)?;
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let attr = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("typed_header"))
+        .ok_or_else(|| Error::new_spanned(name, "missing `#[typed_header(\"...\")]` attribute"))?;
+    let header = attr.parse_args::<LitStr>()?;
+    if !is_header_name(&header.value()) {
+        return Err(Error::new_spanned(
+            header,
+            "expected a lowercase header name",
+        ));
+    }
+
+    // The newtype is decoded by the `FromStr` and encoded by the `Display` of the field.
+    let (field, construct) = match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let index = Index::from(0);
+                (quote!(#index), quote!(Self))
+            }
+            Fields::Named(fields) if fields.named.len() == 1 => {
+                let ident = &fields.named[0].ident;
+                (quote!(#ident), quote!(|#ident| Self { #ident }))
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "`TypedHeader` expects a struct with a single field",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "`TypedHeader` expects a struct with a single field",
+            ))
+        }
+    };
+
+    let stream = quote! {
+        impl #impl_generics viz_core::headers::Header for #name #ty_generics #where_clause {
+            fn name() -> &'static viz_core::header::HeaderName {
+                static NAME: viz_core::header::HeaderName =
+                    viz_core::header::HeaderName::from_static(#header);
+                &NAME
+            }
+
+            fn decode<'i, I>(values: &mut I) -> ::core::result::Result<Self, viz_core::headers::Error>
+            where
+                I: ::core::iter::Iterator<Item = &'i viz_core::header::HeaderValue>,
+            {
+                values
+                    .next()
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse().ok())
+                    .map(#construct)
+                    .ok_or_else(viz_core::headers::Error::invalid)
+            }
+
+            fn encode<E>(&self, values: &mut E)
+            where
+                E: ::core::iter::Extend<viz_core::header::HeaderValue>,
+            {
+                // The `encode` can not fail, so an invalid value is only reported in debug builds.
+                let value = ::std::string::ToString::to_string(&self.#field);
+                match viz_core::header::HeaderValue::from_str(&value) {
+                    ::core::result::Result::Ok(value) => values.extend(::core::iter::once(value)),
+                    ::core::result::Result::Err(_) => ::core::debug_assert!(
+                        false,
+                        "{:?} is not a valid value of the `{}` header",
+                        value,
+                        #header,
+                    ),
+                }
+            }
+        }
+    };
+
+    Ok(stream.into())
+}
@@ -0,0 +1,206 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, LitStr, Result, Type};
+
+/// The `:name+` and `:name*` parameters match the rest of the path, so the `/`s are kept.
+type CatchAll = bool;
+
+/// A piece of the pattern.
+enum Piece {
+    Static(String),
+    /// A named parameter, `:name`, `:name+` or `:name*`, and it is optional if `:name?`.
+    Param(String, bool, CatchAll),
+}
+
+pub(crate) fn generate(input: TokenStream) -> Result<TokenStream> {
+    let ast = syn::parse::<DeriveInput>(input)?;
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let attr = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("typed_path"))
+        .ok_or_else(|| Error::new_spanned(name, "missing `#[typed_path(\"...\")]` attribute"))?;
+    let path = attr.parse_args::<LitStr>()?;
+    let pieces = parse(&path)?;
+
+    let fields = match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return Err(Error::new_spanned(
+                    name,
+                    "`TypedPath` expects a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "`TypedPath` expects a struct with named fields",
+            ))
+        }
+    };
+
+    let params = pieces
+        .iter()
+        .filter_map(|piece| match piece {
+            Piece::Param(name, optional, _) => Some((name, *optional)),
+            Piece::Static(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    for (param, _) in &params {
+        if !fields
+            .iter()
+            .any(|field| field.ident.as_ref().is_some_and(|ident| ident == param))
+        {
+            return Err(Error::new_spanned(
+                &path,
+                format!("the parameter `{param}` is not a field of `{name}`"),
+            ));
+        }
+    }
+
+    let mut extractors = Vec::new();
+    for field in &fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let Some((_, optional)) = params.iter().find(|(param, _)| ident == param) else {
+            return Err(Error::new_spanned(
+                ident,
+                format!(
+                    "the field `{ident}` is not a parameter of `{}`",
+                    path.value()
+                ),
+            ));
+        };
+        let key = ident.to_string();
+        if *optional {
+            if !is_option(&field.ty) {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    format!("the optional parameter `{key}` expects an `Option`"),
+                ));
+            }
+            extractors.push(quote!(#ident: params.find_optional(#key)?));
+        } else {
+            extractors.push(quote!(#ident: params.find(#key)?));
+        }
+    }
+
+    let segments = pieces.iter().map(|piece| match piece {
+        Piece::Static(s) => quote!(uri.push_str(#s);),
+        Piece::Param(param, optional, catch_all) => {
+            let ident = syn::Ident::new(param, path.span());
+            if *optional {
+                quote! {
+                    if let Some(value) = &self.#ident {
+                        viz_core::types::encode_path_param(&mut uri, value, #catch_all);
+                    }
+                }
+            } else {
+                quote!(viz_core::types::encode_path_param(&mut uri, &self.#ident, #catch_all);)
+            }
+        }
+    });
+
+    let stream = quote! {
+        impl #impl_generics viz_core::types::TypedPath for #name #ty_generics #where_clause {
+            const PATH: &'static str = #path;
+
+            fn to_uri(&self) -> ::std::string::String {
+                let mut uri = ::std::string::String::new();
+                #(#segments)*
+                uri
+            }
+        }
+
+        impl #impl_generics viz_core::FromRequestParts for #name #ty_generics #where_clause {
+            type Error = viz_core::types::ParamsError;
+
+            #[allow(unused)]
+            async fn extract_parts(
+                req: &viz_core::Request,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                let params = &<viz_core::Request as viz_core::RequestExt>::route_info(req).params;
+                Ok(Self { #(#extractors),* })
+            }
+        }
+
+        impl #impl_generics viz_core::FromRequest for #name #ty_generics #where_clause {
+            type Error = viz_core::types::ParamsError;
+
+            async fn extract(
+                req: &mut viz_core::Request,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                <Self as viz_core::FromRequestParts>::extract_parts(req).await
+            }
+        }
+    };
+
+    Ok(stream.into())
+}
+
+/// Parses the pattern into the static pieces and the named parameters.
+fn parse(path: &LitStr) -> Result<Vec<Piece>> {
+    let value = path.value();
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.extend(chars.next()),
+            ':' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                if name.is_empty() {
+                    return Err(Error::new_spanned(
+                        path,
+                        "expected a parameter name after `:`",
+                    ));
+                }
+                let modifier = chars.next_if(|c| matches!(c, '?' | '+' | '*'));
+                if pieces
+                    .iter()
+                    .any(|piece| matches!(piece, Piece::Param(n, ..) if *n == name))
+                {
+                    return Err(Error::new_spanned(
+                        path,
+                        format!("the parameter `{name}` is duplicated"),
+                    ));
+                }
+                if !text.is_empty() {
+                    pieces.push(Piece::Static(std::mem::take(&mut text)));
+                }
+                pieces.push(Piece::Param(
+                    name,
+                    modifier == Some('?'),
+                    matches!(modifier, Some('+' | '*')),
+                ));
+            }
+            '*' | '+' => {
+                return Err(Error::new_spanned(
+                    path,
+                    format!("the unnamed `{c}` is not supported, use a named `:name{c}`"),
+                ))
+            }
+            _ => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        pieces.push(Piece::Static(text));
+    }
+
+    Ok(pieces)
+}
+
+fn is_option(ty: &Type) -> bool {
+    matches!(ty, Type::Path(ty) if ty.qself.is_none()
+        && ty.path.segments.last().is_some_and(|seg| seg.ident == "Option"))
+}
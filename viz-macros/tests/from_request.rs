@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use viz_core::{
+    header::CONTENT_TYPE,
+    headers::ContentType,
+    types::{Header, Json, Params, RouteInfo, State},
+    Body, Error, IntoResponse, Request, RequestExt, Response, StatusCode,
+};
+use viz_macros::FromRequest;
+
+#[derive(Clone)]
+struct Db(&'static str);
+
+#[derive(FromRequest)]
+struct CreatePost {
+    db: State<Db>,
+    content_type: Option<Header<ContentType>>,
+    tags: Json<Vec<String>>,
+}
+
+#[derive(FromRequest)]
+#[from_request(parts)]
+struct Context(State<Db>, Option<State<u8>>);
+
+#[derive(FromRequest)]
+#[from_request(parts)]
+struct Nothing;
+
+#[derive(FromRequest)]
+#[from_request(parts)]
+struct Many(
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+    State<u8>,
+);
+
+#[derive(FromRequest)]
+#[from_request(rejection = Rejection)]
+struct Show<T: Send + 'static> {
+    id: Params<T>,
+}
+
+struct Rejection(Error);
+
+impl From<Error> for Rejection {
+    fn from(e: Error) -> Self {
+        Self(e)
+    }
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+fn route(id: &str) -> Arc<RouteInfo> {
+    Arc::new(RouteInfo {
+        id: 0,
+        pattern: "/posts/:id".to_string(),
+        params: vec![("id", id)].into(),
+    })
+}
+
+#[tokio::test]
+async fn derive_from_request() {
+    let mut req = Request::new(Body::Full(r#"["viz","rust"]"#.into()));
+    req.headers_mut()
+        .insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    req.set_state(Db("pg"));
+
+    let post = req.extract::<CreatePost>().await.unwrap();
+    assert_eq!(post.db.0 .0, "pg");
+    assert!(post.content_type.is_some());
+    assert_eq!(post.tags.0, ["viz", "rust"]);
+
+    let Context(db, n) = req.extract_parts::<Context>().await.unwrap();
+    assert_eq!(db.0 .0, "pg");
+    assert!(n.is_none());
+
+    assert!(req.extract_parts::<Nothing>().await.is_ok());
+
+    assert!(req.extract_parts::<Many>().await.is_err());
+    req.set_state(9u8);
+    let Many(s0, s1, s2, s3, s4, s5, s6, s7, s8, s9, s10, s11, s12, s13) =
+        req.extract_parts().await.unwrap();
+    let sum = [s0, s1, s2, s3, s4, s5, s6, s7, s8, s9, s10, s11, s12, s13]
+        .into_iter()
+        .map(|State(n)| n)
+        .sum::<u8>();
+    assert_eq!(sum, 126);
+
+    let mut req = Request::default();
+    assert!(req.extract::<CreatePost>().await.is_err());
+
+    req.extensions_mut().insert(route("x"));
+    let Err(Rejection(e)) = req.extract::<Show<u64>>().await else {
+        panic!("the params should be invalid");
+    };
+    assert_eq!(
+        e.into_response().status(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    req.extensions_mut().insert(route("1"));
+    let show = req.extract::<Show<u64>>().await.ok().unwrap();
+    assert_eq!(show.id.0, 1);
+}
@@ -1,20 +1,35 @@
 #![allow(clippy::unused_async)]
 #![allow(clippy::unnecessary_wraps)]
 
-use viz_core::{Error, FromRequest, Handler, IntoResponse, Request, Result, StatusCode};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use http_body_util::BodyExt;
+use viz_core::{
+    types::{Limits, State},
+    Error, FromRequest, FromRequestParts, Handler, IntoResponse, Request, Response, Result,
+    StatusCode, Transform,
+};
 use viz_macros::handler;
 
 #[derive(Debug)]
 struct Foo;
 
-impl FromRequest for Foo {
+impl FromRequestParts for Foo {
     type Error = Error;
 
-    async fn extract(_: &mut Request) -> Result<Self> {
+    async fn extract_parts(_: &Request) -> Result<Self> {
         Ok(Foo)
     }
 }
 
+impl FromRequest for Foo {
+    type Error = Error;
+
+    async fn extract(req: &mut Request) -> Result<Self> {
+        Self::extract_parts(req).await
+    }
+}
+
 #[derive(Debug)]
 struct Bar;
 
@@ -78,6 +93,16 @@ async fn j(_: Foo) -> Result<StatusCode> {
     Err(MyError("custom error".to_string()).into())
 }
 
+#[handler]
+async fn k(_: Foo, req: Request) -> String {
+    req.uri().path().to_string()
+}
+
+#[handler]
+async fn l(req: Request) -> String {
+    req.uri().path().to_string()
+}
+
 #[handler]
 fn aa() -> impl IntoResponse {}
 
@@ -129,6 +154,8 @@ async fn test_handler() -> anyhow::Result<()> {
     assert!(h.call(Request::default()).await.is_ok());
     assert!(i.call(Request::default()).await.is_ok());
     assert!(j.call(Request::default()).await.is_err());
+    assert!(k.call(Request::default()).await.is_ok());
+    assert!(l.call(Request::default()).await.is_ok());
 
     assert!(aa.call(Request::default()).await.is_ok());
     assert!(bb.call(Request::default()).await.is_ok());
@@ -142,3 +169,126 @@ async fn test_handler() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+trait Store: Send + Sync {
+    fn name() -> String;
+}
+
+struct Memory;
+
+impl Store for Memory {
+    fn name() -> String {
+        "memory".to_string()
+    }
+}
+
+#[handler]
+async fn store<S: Store>(_: Foo) -> String {
+    S::name()
+}
+
+#[handler]
+fn repeat<const N: usize>() -> String {
+    "viz".repeat(N)
+}
+
+#[handler(with = State::new(7u8))]
+async fn with_state(State(n): State<u8>) -> Result<String> {
+    Ok(n.to_string())
+}
+
+#[handler(limits = "text:1kb, json: 2MB")]
+async fn with_limits(limits: Limits) -> String {
+    format!("{:?}/{:?}", limits.get("text"), limits.get("json"))
+}
+
+/// Counts how many times the middleware is built.
+struct Counting(&'static AtomicUsize);
+
+impl<H> Transform<H> for Counting {
+    type Output = H;
+
+    fn transform(&self, handler: H) -> Self::Output {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        handler
+    }
+}
+
+static COUNTED: AtomicUsize = AtomicUsize::new(0);
+
+#[handler(with = Counting(&COUNTED))]
+async fn counted() -> String {
+    "counted".to_string()
+}
+
+static COUNTED_GENERIC: AtomicUsize = AtomicUsize::new(0);
+
+#[handler(with = Counting(&COUNTED_GENERIC))]
+fn counted_generic<const N: usize>() -> String {
+    N.to_string()
+}
+
+#[derive(Clone)]
+struct Users {
+    prefix: String,
+}
+
+impl Users {
+    #[handler]
+    async fn show(&self, _: Foo) -> Result<String> {
+        Ok(format!("{}: show", self.prefix))
+    }
+
+    #[handler(with = State::new(1u8))]
+    fn count(self, State(n): State<u8>) -> String {
+        format!("{}: {n}", self.prefix)
+    }
+
+    #[handler]
+    async fn path(&self, _: Foo, req: Request) -> String {
+        format!("{}: {}", self.prefix, req.uri().path())
+    }
+}
+
+async fn text<H>(handler: &H) -> anyhow::Result<String>
+where
+    H: Handler<Request, Output = Result<Response>>,
+{
+    let body = handler.call(Request::default()).await?.into_body();
+    Ok(String::from_utf8(
+        body.collect().await?.to_bytes().to_vec(),
+    )?)
+}
+
+#[tokio::test]
+async fn test_handler_generics_and_methods() -> anyhow::Result<()> {
+    assert_eq!(text(&store::<Memory>::default()).await?, "memory");
+    assert_eq!(text(&repeat::<2>::default()).await?, "vizviz");
+    assert_eq!(text(&with_state).await?, "7");
+    assert_eq!(text(&with_limits).await?, "Some(1024)/Some(2097152)");
+
+    for _ in 0..3 {
+        assert_eq!(text(&counted).await?, "counted");
+    }
+    assert_eq!(COUNTED.load(Ordering::SeqCst), 1);
+
+    let handler = counted_generic::<3>::default();
+    for _ in 0..3 {
+        assert_eq!(text(&handler.clone()).await?, "3");
+    }
+    assert_eq!(COUNTED_GENERIC.load(Ordering::SeqCst), 1);
+
+    let users = Users {
+        prefix: "users".to_string(),
+    };
+    assert_eq!(text(&users.show_handler()).await?, "users: show");
+    assert_eq!(text(&users.count_handler()).await?, "users: 1");
+    assert_eq!(text(&users.path_handler()).await?, "users: /");
+    assert_eq!(text(&k).await?, "/");
+    assert_eq!(
+        users.show(Foo).await.map_err(|_| anyhow::anyhow!("show"))?,
+        "users: show"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use http_body_util::BodyExt;
+use viz_core::{
+    header::{CACHE_CONTROL, CONTENT_TYPE},
+    Error, IntoResponse, Response, Result, StatusCode,
+};
+use viz_macros::IntoResponse;
+
+#[derive(IntoResponse)]
+#[header("x-service", "users")]
+enum UserError {
+    #[status(404)]
+    #[text("user {id} not found")]
+    NotFound {
+        id: u64,
+    },
+    #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+    #[header(CACHE_CONTROL, "no-cache")]
+    #[json]
+    Invalid(Vec<String>),
+    #[status(409)]
+    #[text("{0} is taken by {1}")]
+    Conflict(String, u64),
+    #[status(503)]
+    #[text]
+    Unavailable(&'static str),
+    Gone,
+}
+
+#[derive(IntoResponse)]
+#[status(400)]
+#[json]
+enum FormError {
+    Fields(Vec<String>),
+    #[text]
+    Message(String),
+    Empty,
+}
+
+#[derive(IntoResponse)]
+#[status(201)]
+#[header("location", "/users/1")]
+struct Created(&'static str);
+
+async fn text(res: Response) -> String {
+    String::from_utf8(res.into_body().collect().await.unwrap().to_bytes().to_vec()).unwrap()
+}
+
+fn find(id: u64) -> Result<Created> {
+    if id == 0 {
+        Err(UserError::NotFound { id })?;
+    }
+    Ok(Created("created"))
+}
+
+#[tokio::test]
+async fn derive_into_response() {
+    let res = UserError::NotFound { id: 7 }.into_response();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert_eq!(res.headers()["x-service"], "users");
+    assert_eq!(text(res).await, "user 7 not found");
+
+    let res = UserError::Invalid(vec!["name".to_string()]).into_response();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(res.headers()[CACHE_CONTROL], "no-cache");
+    assert_eq!(res.headers()[CONTENT_TYPE], "application/json");
+    assert_eq!(text(res).await, r#"["name"]"#);
+
+    let res = UserError::Conflict("viz".to_string(), 1).into_response();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    assert_eq!(text(res).await, "viz is taken by 1");
+
+    let res = UserError::Unavailable("down").into_response();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(text(res).await, "down");
+
+    let res = UserError::Gone.into_response();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-service"], "users");
+
+    let res = FormError::Fields(vec!["name".to_string()]).into_response();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(res.headers()[CONTENT_TYPE], "application/json");
+    assert_eq!(text(res).await, r#"["name"]"#);
+
+    let res = FormError::Message("invalid".to_string()).into_response();
+    assert_eq!(res.headers()[CONTENT_TYPE], "text/plain; charset=utf-8");
+    assert_eq!(text(res).await, "invalid");
+
+    let res = FormError::Empty.into_response();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(text(res).await, "");
+
+    let res = Created("created").into_response();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    assert_eq!(res.headers()["location"], "/users/1");
+    assert_eq!(text(res).await, "created");
+
+    let Err(Error::Responder(res)) = find(0) else {
+        panic!("the user should not be found");
+    };
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert!(find(1).is_ok());
+}
@@ -0,0 +1,72 @@
+#![allow(clippy::unused_async)]
+
+use http_body_util::BodyExt;
+use viz_core::{
+    types::{Params, State},
+    Endpoint, Handler, Method, Request, Result, StatusCode,
+};
+use viz_macros::{delete, get, post};
+use viz_router::{routes, Router, Tree};
+
+#[get("/users/:id")]
+async fn show_user(Params(id): Params<u64>) -> String {
+    format!("user {id}")
+}
+
+#[post("/users")]
+async fn create_user() -> StatusCode {
+    StatusCode::CREATED
+}
+
+#[get("/users")]
+async fn list_users() -> Result<&'static str> {
+    Ok("users")
+}
+
+#[get("/count", with = State::new(2usize))]
+async fn count(State(n): State<usize>) -> String {
+    n.to_string()
+}
+
+#[delete("/items")]
+async fn clear<const N: usize>() -> String {
+    format!("cleared {N}")
+}
+
+#[test]
+fn route_endpoint() {
+    assert_eq!(show_user::PATH, "/users/:id");
+    assert_eq!(show_user::METHOD, Method::GET);
+    assert_eq!(create_user::PATH, "/users");
+    assert_eq!(create_user::METHOD, Method::POST);
+    assert_eq!(clear::<1>::PATH, "/items");
+    assert_eq!(clear::<1>::METHOD, Method::DELETE);
+}
+
+#[tokio::test]
+async fn route_mount() -> anyhow::Result<()> {
+    let tree = Tree::from(Router::new().mount(routes![show_user, create_user, list_users]));
+
+    let (h, _) = tree.find(&Method::GET, "/users").unwrap();
+    let resp = h.call(Request::default()).await?;
+    assert_eq!(resp.into_body().collect().await?.to_bytes(), "users");
+
+    let (h, _) = tree.find(&Method::POST, "/users").unwrap();
+    let resp = h.call(Request::default()).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    assert!(tree.find(&Method::GET, "/users/1").is_some());
+    assert!(tree.find(&Method::DELETE, "/users/1").is_none());
+
+    let tree = Tree::from(Router::new().mount(routes![count, clear::<3>::default()]));
+
+    let (h, _) = tree.find(&Method::GET, "/count").unwrap();
+    let resp = h.call(Request::default()).await?;
+    assert_eq!(resp.into_body().collect().await?.to_bytes(), "2");
+
+    let (h, _) = tree.find(&Method::DELETE, "/items").unwrap();
+    let resp = h.call(Request::default()).await?;
+    assert_eq!(resp.into_body().collect().await?.to_bytes(), "cleared 3");
+
+    Ok(())
+}
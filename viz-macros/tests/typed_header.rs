@@ -0,0 +1,70 @@
+use viz_core::{
+    header::CACHE_CONTROL,
+    headers::{CacheControl, ContentLength, HeaderMapExt},
+    types::{Header, Headers, TypedHeaders},
+    IntoResponse, Request, RequestExt, StatusCode,
+};
+use viz_macros::TypedHeader;
+
+#[derive(TypedHeader, Debug, PartialEq)]
+#[typed_header("x-tenant-id")]
+struct TenantId(u64);
+
+#[derive(TypedHeader, Debug, PartialEq)]
+#[typed_header("x-request-id")]
+struct RequestId {
+    id: String,
+}
+
+#[tokio::test]
+async fn derive_typed_header() {
+    let mut req = Request::default();
+    req.headers_mut()
+        .insert("x-tenant-id", " 7 ".parse().unwrap());
+    req.headers_mut().typed_insert(RequestId {
+        id: "abc".to_string(),
+    });
+
+    let Header(tenant) = req.extract_parts::<Header<TenantId>>().await.unwrap();
+    assert_eq!(tenant, TenantId(7));
+
+    let Headers((tenant, request)) = req
+        .extract_parts::<Headers<(TenantId, RequestId)>>()
+        .await
+        .unwrap();
+    assert_eq!(tenant, TenantId(7));
+    assert_eq!(request.id, "abc");
+
+    let Err(e) = req
+        .extract_parts::<Headers<(TenantId, ContentLength)>>()
+        .await
+    else {
+        panic!("the content length should be missing");
+    };
+    assert_eq!(e.to_string(), "Missing header name content-length");
+
+    req.headers_mut()
+        .insert("x-tenant-id", "x".parse().unwrap());
+    let Err(e) = req.extract_parts::<Header<TenantId>>().await else {
+        panic!("the tenant id should be invalid");
+    };
+    assert_eq!(e.to_string(), "Invalid header name x-tenant-id");
+
+    let res = TypedHeaders(
+        (TenantId(1), CacheControl::new().with_no_cache()),
+        (StatusCode::CREATED, "created"),
+    )
+    .into_response();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    assert_eq!(res.headers()["x-tenant-id"], "1");
+    assert_eq!(res.headers()[CACHE_CONTROL], "no-cache");
+}
+
+#[test]
+#[should_panic(expected = "is not a valid value of the `x-request-id` header")]
+fn typed_header_invalid_value() {
+    let mut headers = viz_core::header::HeaderMap::new();
+    headers.typed_insert(RequestId {
+        id: "a\nb".to_string(),
+    });
+}
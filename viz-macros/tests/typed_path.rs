@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use viz_core::{
+    types::{RouteInfo, TypedPath},
+    Request, RequestExt,
+};
+use viz_macros::TypedPath;
+
+#[derive(Debug, TypedPath)]
+#[typed_path("/users/:id/posts/:post_id")]
+struct UserPost {
+    id: u64,
+    post_id: String,
+}
+
+#[derive(Debug, TypedPath)]
+#[typed_path("/files/:name?")]
+struct File {
+    name: Option<String>,
+}
+
+#[derive(Debug, TypedPath)]
+#[typed_path("/health")]
+struct Health;
+
+#[derive(Debug, TypedPath)]
+#[typed_path("/docs/:lang/:rest*")]
+struct Doc {
+    lang: String,
+    rest: String,
+}
+
+fn request(pattern: &str, params: Vec<(&str, &str)>) -> Request {
+    let mut req = Request::default();
+    req.extensions_mut().insert(Arc::from(RouteInfo {
+        id: 0,
+        pattern: pattern.to_string(),
+        params: params.into(),
+    }));
+    req
+}
+
+#[test]
+fn typed_path_to_uri() {
+    assert_eq!(UserPost::PATH, "/users/:id/posts/:post_id");
+    assert_eq!(
+        UserPost {
+            id: 1,
+            post_id: "viz".to_string()
+        }
+        .to_uri(),
+        "/users/1/posts/viz"
+    );
+
+    assert_eq!(File::PATH, "/files/:name?");
+    assert_eq!(File { name: None }.to_uri(), "/files/");
+    assert_eq!(
+        File {
+            name: Some("logo.svg".to_string())
+        }
+        .to_uri(),
+        "/files/logo.svg"
+    );
+
+    assert_eq!(Health.to_uri(), "/health");
+
+    assert_eq!(
+        Doc {
+            lang: "a/b?c#d".to_string(),
+            rest: "100% sure/x y".to_string(),
+        }
+        .to_uri(),
+        "/docs/a%2Fb%3Fc%23d/100%25%20sure/x%20y"
+    );
+}
+
+#[tokio::test]
+async fn typed_path_extract() {
+    let mut req = request(UserPost::PATH, vec![("id", "1"), ("post_id", "viz")]);
+    let post = req.extract::<UserPost>().await.unwrap();
+    assert_eq!(post.id, 1);
+    assert_eq!(post.post_id, "viz");
+
+    let req = request(UserPost::PATH, vec![("id", "x"), ("post_id", "viz")]);
+    assert!(req.extract_parts::<UserPost>().await.is_err());
+
+    let req = request(File::PATH, vec![("name", "")]);
+    assert!(req.extract_parts::<File>().await.unwrap().name.is_none());
+
+    let req = request(File::PATH, vec![("name", "logo.svg")]);
+    assert_eq!(
+        req.extract_parts::<File>().await.unwrap().name.as_deref(),
+        Some("logo.svg")
+    );
+}
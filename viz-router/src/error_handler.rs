@@ -0,0 +1,217 @@
+use std::{error::Error as StdError, fmt, sync::Arc};
+
+use viz_core::{
+    header::HeaderName, types::RouteInfo, Error, Handler, IntoResponse, Method, Request, Response,
+    Result, StatusCode, Transform,
+};
+
+type Mapping = Arc<dyn Fn(&Error, &ErrorContext) -> Option<Response> + Send + Sync>;
+
+type Report = Arc<dyn Fn(&Error, &ErrorContext) + Send + Sync>;
+
+/// The request metadata of an error.
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    /// The HTTP method.
+    pub method: Method,
+    /// The request URI's path.
+    pub path: String,
+    /// The matched route.
+    pub route: Option<Arc<RouteInfo>>,
+}
+
+/// A router-level error handler, maps the errors of the handlers to the responses.
+///
+/// The mappings are tried in order, then the internal errors are reported and responded
+/// with `500`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let router = Router::new()
+///     .get("/users/:id", show)
+///     .error_handler(
+///         ErrorHandler::new()
+///             .map(|e: &sqlx::Error, _| {
+///                 matches!(e, sqlx::Error::RowNotFound).then_some(StatusCode::NOT_FOUND)
+///             })
+///             .status::<std::num::ParseIntError>(StatusCode::BAD_REQUEST)
+///             .production(true),
+///     );
+/// ```
+#[derive(Clone)]
+pub struct ErrorHandler {
+    mappings: Vec<Mapping>,
+    report: Option<Report>,
+    production: bool,
+    safe_headers: Vec<HeaderName>,
+}
+
+impl ErrorHandler {
+    /// Creates a new error handler.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps the error of the type `E` to a response, returns `None` to try the next mapping.
+    #[must_use]
+    pub fn map<E, F, R>(mut self, f: F) -> Self
+    where
+        E: StdError + 'static,
+        F: Fn(&E, &ErrorContext) -> Option<R> + Send + Sync + 'static,
+        R: IntoResponse,
+    {
+        self.mappings.push(Arc::new(move |err, cx| {
+            err.downcast_ref::<E>()
+                .and_then(|e| f(e, cx))
+                .map(IntoResponse::into_response)
+        }));
+        self
+    }
+
+    /// Maps the error of the type `E` to the status code, the error message is the body.
+    #[must_use]
+    pub fn status<E>(self, status: StatusCode) -> Self
+    where
+        E: StdError + 'static,
+    {
+        self.map(move |e: &E, _| Some((status, e.to_string())))
+    }
+
+    /// Reports the unmapped internal errors, they are logged by [`tracing`] by default.
+    #[must_use]
+    pub fn report<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Error, &ErrorContext) + Send + Sync + 'static,
+    {
+        self.report = Some(Arc::new(f));
+        self
+    }
+
+    /// Hides the messages of the internal errors from the clients or not, `false` by default.
+    ///
+    /// In production, the internal errors are responded with an empty `500`, only the safe
+    /// headers of their responses are kept.
+    #[must_use]
+    pub fn production(mut self, production: bool) -> Self {
+        self.production = production;
+        self
+    }
+
+    /// Keeps the header of the internal error responses in production, the `X-Request-Id` is
+    /// kept by default.
+    #[must_use]
+    pub fn safe_header(mut self, name: HeaderName) -> Self {
+        self.safe_headers.push(name);
+        self
+    }
+
+    fn handle(&self, err: Error, cx: &ErrorContext) -> Response {
+        if let Some(resp) = self.mappings.iter().find_map(|f| f(&err, cx)) {
+            return resp;
+        }
+
+        match err {
+            Error::Responder(resp) => resp,
+            Error::Report(_, _) | Error::Boxed(_) => {
+                if let Some(report) = &self.report {
+                    report(&err, cx);
+                } else {
+                    tracing::error!(
+                        method = %cx.method,
+                        path = %cx.path,
+                        route = cx.route.as_ref().map(|info| info.pattern.as_str()),
+                        error = %err,
+                        "internal error"
+                    );
+                }
+                match err {
+                    Error::Report(_, resp)
+                        if self.production && resp.status().is_server_error() =>
+                    {
+                        self.hide(&resp)
+                    }
+                    Error::Report(_, resp) => resp,
+                    _ if self.production => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+                    _ => err.into_response(),
+                }
+            }
+        }
+    }
+
+    /// Replaces the internal error response with an empty `500`, keeps the safe headers.
+    fn hide(&self, resp: &Response) -> Response {
+        let mut hidden = StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        for name in &self.safe_headers {
+            if let Some(value) = resp.headers().get(name) {
+                hidden.headers_mut().insert(name, value.clone());
+            }
+        }
+        hidden
+    }
+}
+
+impl Default for ErrorHandler {
+    fn default() -> Self {
+        Self {
+            mappings: Vec::new(),
+            report: None,
+            production: false,
+            safe_headers: vec![HeaderName::from_static("x-request-id")],
+        }
+    }
+}
+
+impl fmt::Debug for ErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorHandler")
+            .field("mappings", &self.mappings.len())
+            .field("production", &self.production)
+            .field("safe_headers", &self.safe_headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H> Transform<H> for ErrorHandler
+where
+    H: Clone,
+{
+    type Output = ErrorHandlerMiddleware<H>;
+
+    fn transform(&self, h: H) -> Self::Output {
+        ErrorHandlerMiddleware {
+            h,
+            handler: self.clone(),
+        }
+    }
+}
+
+/// A middleware for handling the errors by the [`ErrorHandler`].
+#[derive(Debug, Clone)]
+pub struct ErrorHandlerMiddleware<H> {
+    h: H,
+    handler: ErrorHandler,
+}
+
+#[viz_core::async_trait]
+impl<H, O> Handler<Request> for ErrorHandlerMiddleware<H>
+where
+    H: Handler<Request, Output = Result<O>>,
+    O: IntoResponse,
+{
+    type Output = Result<Response>;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let cx = ErrorContext {
+            method: req.method().clone(),
+            path: req.uri().path().to_string(),
+            route: req.extensions().get::<Arc<RouteInfo>>().cloned(),
+        };
+
+        match self.h.call(req).await {
+            Ok(resp) => Ok(resp.into_response()),
+            Err(err) => Ok(self.handler.handle(err, &cx)),
+        }
+    }
+}
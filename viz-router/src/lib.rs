@@ -12,6 +12,9 @@
 #[macro_use]
 pub(crate) mod macros;
 
+mod error_handler;
+pub use error_handler::{ErrorContext, ErrorHandler, ErrorHandlerMiddleware};
+
 mod resources;
 pub use resources::Resources;
 
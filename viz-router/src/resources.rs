@@ -61,7 +61,7 @@ impl Resources {
             .find(|(p, _)| p == &kind)
             .map(|(_, r)| r)
         {
-            Some(r) => *r = route.into_iter().fold(r.clone(), |r, (m, h)| r.on(m, h)),
+            Some(r) => *r = r.clone().merge(route),
             None => {
                 self.routes.push((kind, route));
             }
@@ -182,15 +182,7 @@ impl Resources {
             routes: self
                 .routes
                 .into_iter()
-                .map(|(path, route)| {
-                    (
-                        path,
-                        route
-                            .into_iter()
-                            .map(|(method, handler)| (method, f(handler)))
-                            .collect(),
-                    )
-                })
+                .map(|(path, route)| (path, route.map_handler(&f)))
                 .collect(),
         }
     }
@@ -3,8 +3,21 @@
 use core::fmt;
 
 use viz_core::{
-    BoxHandler, Handler, HandlerExt, IntoResponse, Method, Next, Request, Response, Result,
-    Transform,
+    BoxHandler, Endpoint, Handler, HandlerExt, IntoResponse, Method, Next, Request, Response,
+    Result, Transform,
+};
+
+#[cfg(feature = "openapi")]
+use viz_core::{
+    openapi::{Operation, OperationHandler},
+    IntoHandler,
+};
+
+#[cfg(feature = "cors")]
+use viz_core::{
+    header::{HeaderValue, ALLOW},
+    middleware::cors,
+    StatusCode,
 };
 
 macro_rules! export_internal_verb {
@@ -39,6 +52,11 @@ macro_rules! export_verb {
 #[derive(Clone, Default)]
 pub struct Route {
     pub(crate) methods: Vec<(Method, BoxHandler)>,
+    #[cfg(feature = "openapi")]
+    pub(crate) operations: Vec<(Method, Operation)>,
+    /// The `OPTIONS` handler is generated by the [`Route::cors`], it is not an operation.
+    #[cfg(feature = "cors")]
+    pub(crate) preflight: bool,
 }
 
 impl Route {
@@ -47,12 +65,21 @@ impl Route {
     pub fn new() -> Self {
         Self {
             methods: Vec::new(),
+            #[cfg(feature = "openapi")]
+            operations: Vec::new(),
+            #[cfg(feature = "cors")]
+            preflight: false,
         }
     }
 
     /// Appends a HTTP verb and handler pair into the route.
     #[must_use]
     pub fn push(mut self, method: Method, handler: BoxHandler) -> Self {
+        #[cfg(feature = "cors")]
+        if method == Method::OPTIONS {
+            self.preflight = false;
+        }
+
         match self
             .methods
             .iter_mut()
@@ -117,9 +144,107 @@ impl Route {
     where
         F: Fn(BoxHandler) -> BoxHandler,
     {
-        self.into_iter()
-            .map(|(method, handler)| (method, f(handler)))
-            .collect()
+        Self {
+            methods: self
+                .methods
+                .into_iter()
+                .map(|(method, handler)| (method, f(handler)))
+                .collect(),
+            #[cfg(feature = "openapi")]
+            operations: self.operations,
+            #[cfg(feature = "cors")]
+            preflight: self.preflight,
+        }
+    }
+
+    /// Merges the handlers of the other route into the route, the same verbs are replaced.
+    #[must_use]
+    pub(crate) fn merge(self, other: Self) -> Self {
+        #[cfg(feature = "openapi")]
+        let operations = other.operations.clone();
+        #[cfg(feature = "cors")]
+        let preflight = other.preflight;
+
+        #[allow(unused_mut)]
+        let mut route = other
+            .into_iter()
+            .fold(self, |route, (method, handler)| route.on(method, handler));
+
+        #[cfg(feature = "cors")]
+        {
+            route.preflight |= preflight;
+        }
+
+        #[cfg(feature = "openapi")]
+        let route = operations
+            .into_iter()
+            .fold(route, |route, (method, operation)| {
+                route.operation(method, operation)
+            });
+
+        route
+    }
+
+    /// Describes the operation of the HTTP verb for the `OpenAPI` document.
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn operation(mut self, method: Method, operation: Operation) -> Self {
+        match self
+            .operations
+            .iter_mut()
+            .find(|(m, _)| m == method)
+            .map(|(_, o)| o)
+        {
+            Some(o) => *o = operation,
+            None => self.operations.push((method, operation)),
+        }
+        self
+    }
+
+    /// Updates the operation of the HTTP verb, e.g. adds the summary to a derived operation.
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn describe<F>(self, method: Method, f: F) -> Self
+    where
+        F: FnOnce(Operation) -> Operation,
+    {
+        let operation = f(self.get_operation(&method).cloned().unwrap_or_default());
+        self.operation(method, operation)
+    }
+
+    /// Appends a handler with extractors by the specified HTTP verb into the route, the
+    /// operation is derived from the extractors and the output of the handler.
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn api<H, E, O>(self, method: Method, handler: H) -> Self
+    where
+        H: IntoHandler<Request, E> + OperationHandler<E>,
+        H::Handler: Handler<Request, Output = Result<O>> + Clone,
+        O: IntoResponse,
+    {
+        self.on(method.clone(), handler.into_handler())
+            .operation(method, H::operation())
+    }
+
+    /// Checks if the handler of the HTTP verb is the `OPTIONS` handler generated by the CORS.
+    #[cfg(feature = "openapi")]
+    pub(crate) fn is_preflight(&self, method: &Method) -> bool {
+        #[cfg(feature = "cors")]
+        return self.preflight && method == Method::OPTIONS;
+        #[cfg(not(feature = "cors"))]
+        return {
+            let _ = method;
+            false
+        };
+    }
+
+    /// Gets the operation of the HTTP verb.
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn get_operation(&self, method: &Method) -> Option<&Operation> {
+        self.operations
+            .iter()
+            .find_map(|(m, o)| if m == method { Some(o) } else { None })
     }
 
     /// Transforms the types to a middleware and adds it.
@@ -140,6 +265,49 @@ impl Route {
     {
         self.map_handler(|handler| handler.around(f.clone()).boxed())
     }
+
+    /// Adds the CORS middleware, and answers the preflight requests if the route has no
+    /// `OPTIONS` handler.
+    ///
+    /// The other `OPTIONS` requests are answered with `204 No Content` and the `Allow` header
+    /// of the route's methods. The generated handler is only wrapped by the CORS middleware and
+    /// the middleware added after, so it should be added after the other methods and middleware.
+    #[cfg(feature = "cors")]
+    #[must_use]
+    pub fn cors(self, config: cors::Config) -> Self {
+        let has_options = self.methods.iter().any(|(m, _)| m == Method::OPTIONS);
+        let route = self.with(config.clone());
+        if has_options {
+            return route;
+        }
+
+        let allow = route
+            .methods
+            .iter()
+            .map(|(m, _)| m.as_str())
+            .chain([Method::OPTIONS.as_str()])
+            .collect::<Vec<_>>()
+            .join(", ");
+        let allow = HeaderValue::from_str(&allow).ok();
+
+        let mut route = route.push(
+            Method::OPTIONS,
+            config
+                .transform(move |_: Request| {
+                    let allow = allow.clone();
+                    async move {
+                        let mut resp = StatusCode::NO_CONTENT.into_response();
+                        if let Some(allow) = allow {
+                            resp.headers_mut().insert(ALLOW, allow);
+                        }
+                        Ok(resp)
+                    }
+                })
+                .boxed(),
+        );
+        route.preflight = true;
+        route
+    }
 }
 
 impl IntoIterator for Route {
@@ -159,6 +327,10 @@ impl FromIterator<(Method, BoxHandler)> for Route {
     {
         Self {
             methods: iter.into_iter().collect(),
+            #[cfg(feature = "openapi")]
+            operations: Vec::new(),
+            #[cfg(feature = "cors")]
+            preflight: false,
         }
     }
 }
@@ -194,18 +366,55 @@ where
     Route::new().any(handler)
 }
 
+/// Creates a path-route pair from the [`Endpoint`], it is used by the [`routes!`] macro.
+pub fn endpoint<E>(endpoint: E) -> (&'static str, Route)
+where
+    E: Endpoint,
+{
+    (E::PATH, Route::new().on(E::METHOD, endpoint))
+}
+
+/// Collects the [`Endpoint`]s into the path-route pairs, then they can be mounted by
+/// the [`Router::mount`][crate::Router::mount].
+///
+/// # Examples
+///
+/// ```ignore
+/// #[get("/users/:id")]
+/// async fn show_user(Params(id): Params<u64>) -> String {
+///     format!("user {id}")
+/// }
+///
+/// #[post("/users")]
+/// async fn create_user(Json(user): Json<User>) -> Result<StatusCode> {
+///     Ok(StatusCode::CREATED)
+/// }
+///
+/// let app = Router::new().mount(routes![show_user, create_user]);
+/// ```
+#[macro_export]
+macro_rules! routes {
+    ($($endpoint:expr),* $(,)?) => {
+        ::std::vec![$($crate::endpoint($endpoint)),*]
+    };
+}
+
 impl fmt::Debug for Route {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Route")
-            .field(
-                "methods",
-                &self
-                    .methods
-                    .iter()
-                    .map(|(m, _)| m)
-                    .collect::<Vec<&Method>>(),
-            )
-            .finish()
+        let mut s = f.debug_struct("Route");
+        s.field(
+            "methods",
+            &self
+                .methods
+                .iter()
+                .map(|(m, _)| m)
+                .collect::<Vec<&Method>>(),
+        );
+        #[cfg(feature = "openapi")]
+        s.field("operations", &self.operations);
+        #[cfg(feature = "cors")]
+        s.field("preflight", &self.preflight);
+        s.finish()
     }
 }
 
@@ -1,16 +1,32 @@
+use std::fmt;
+
 use viz_core::{
-    BoxHandler, Handler, HandlerExt, IntoResponse, Next, Request, Response, Result, Transform,
+    types::{Factory, Providers, StateCell, StateHandler, TypedPath},
+    BoxHandler, Future, Handler, HandlerExt, IntoResponse, Method, Next, Request, Response, Result,
+    Transform,
 };
 
-use crate::{Resources, Route};
+#[cfg(feature = "cors")]
+use viz_core::middleware::cors;
+
+#[cfg(feature = "openapi")]
+use std::sync::Arc;
+
+#[cfg(feature = "openapi")]
+use viz_core::{
+    openapi::{Info, OpenApi, OperationHandler},
+    IntoHandler, ResponseExt,
+};
+
+use crate::{ErrorHandler, Resources, Route};
 
 macro_rules! export_verb {
     ($name:ident $verb:ty) => {
         #[doc = concat!(" Adds a handler with a path and HTTP `", stringify!($verb), "` verb pair.")]
         #[must_use]
-        pub fn $name<S, H, O>(self, path: S, handler: H) -> Self
+        pub fn $name<P, H, O>(self, path: P, handler: H) -> Self
         where
-            S: AsRef<str>,
+            P: AsRef<str>,
             H: Handler<Request, Output = Result<O>> + Clone,
             O: IntoResponse + Send + 'static,
         {
@@ -19,22 +35,46 @@ macro_rules! export_verb {
     };
 }
 
+macro_rules! export_stateful_verb {
+    ($name:ident $verb:ident) => {
+        #[doc = concat!(" Adds a stateful handler with a path and HTTP `", stringify!($verb), "` verb pair.")]
+        #[must_use]
+        pub fn $name<P, H, E>(self, path: P, handler: H) -> Self
+        where
+            P: AsRef<str>,
+            H: StateHandler<S, E>,
+        {
+            self.stateful(path, Method::$verb, handler)
+        }
+    };
+}
+
 /// A routes collection.
-#[derive(Clone, Debug, Default)]
-pub struct Router {
+///
+/// The `S` is the application state of the routes added by the [`Router::stateful`], the router
+/// can only be served after the state is provided by the [`Router::with_state`].
+///
+/// The router with a state can not be cloned before the state is provided, so the cells of the
+/// state are owned by one router and are filled once.
+pub struct Router<S = ()> {
     pub(crate) routes: Option<Vec<(String, Route)>>,
+    pub(crate) error_handler: Option<ErrorHandler>,
+    pub(crate) providers: Option<Providers>,
+    pub(crate) states: Vec<StateCell<S>>,
 }
 
 impl Router {
     /// Creates an empty `Router`.
     #[must_use]
     pub fn new() -> Self {
-        Self { routes: None }
+        Self::default()
     }
+}
 
-    fn push<S>(routes: &mut Vec<(String, Route)>, path: S, route: Route)
+impl<S> Router<S> {
+    fn push<P>(routes: &mut Vec<(String, Route)>, path: P, route: Route)
     where
-        S: AsRef<str>,
+        P: AsRef<str>,
     {
         let path = path.as_ref();
         match routes
@@ -42,11 +82,7 @@ impl Router {
             .find_map(|(p, r)| if p == path { Some(r) } else { None })
         {
             Some(r) => {
-                *r = route.into_iter().fold(
-                    // original route
-                    r.clone().into_iter().collect(),
-                    |or: Route, (method, handler)| or.on(method, handler),
-                );
+                *r = r.clone().merge(route);
             }
             None => routes.push((path.to_string(), route)),
         }
@@ -54,9 +90,9 @@ impl Router {
 
     /// Inserts a path-route pair into the router.
     #[must_use]
-    pub fn route<S>(mut self, path: S, route: Route) -> Self
+    pub fn route<P>(mut self, path: P, route: Route) -> Self
     where
-        S: AsRef<str>,
+        P: AsRef<str>,
     {
         Self::push(
             self.routes.get_or_insert_with(Vec::new),
@@ -66,11 +102,32 @@ impl Router {
         self
     }
 
+    /// Inserts a route with the pattern of the [`TypedPath`] into the router.
+    #[must_use]
+    pub fn typed_route<P>(self, route: Route) -> Self
+    where
+        P: TypedPath,
+    {
+        self.route(P::PATH, route)
+    }
+
+    /// Mounts the path-route pairs into the router, e.g. collected by the [`routes!`] macro.
+    #[must_use]
+    pub fn mount<I, P>(self, routes: I) -> Self
+    where
+        I: IntoIterator<Item = (P, Route)>,
+        P: AsRef<str>,
+    {
+        routes
+            .into_iter()
+            .fold(self, |router, (path, route)| router.route(path, route))
+    }
+
     /// Nested resources with a path.
     #[must_use]
-    pub fn resources<S>(self, path: S, resource: Resources) -> Self
+    pub fn resources<P>(self, path: P, resource: Resources) -> Self
     where
-        S: AsRef<str>,
+        P: AsRef<str>,
     {
         let mut path = path.as_ref().to_string();
         if !path.ends_with('/') {
@@ -90,10 +147,18 @@ impl Router {
     /// Nested sub-router with a path.
     #[allow(clippy::similar_names)]
     #[must_use]
-    pub fn nest<S>(self, path: S, router: Self) -> Self
+    pub fn nest<P>(mut self, path: P, mut router: Self) -> Self
     where
-        S: AsRef<str>,
+        P: AsRef<str>,
     {
+        if let Some(providers) = router.providers.take() {
+            router = router.with(providers);
+        }
+        if let Some(handler) = router.error_handler.take() {
+            router = router.with(handler);
+        }
+        self.states.append(&mut router.states);
+
         let mut path = path.as_ref().to_string();
         if !path.ends_with('/') {
             path.push('/');
@@ -125,11 +190,85 @@ impl Router {
         trace TRACE
     );
 
+    /// Adds a handler with a path and HTTP verb pair, the handler takes the [`State`] of a
+    /// sub-state of the application state `S` as the first argument.
+    ///
+    /// [`State`]: viz_core::types::State
+    #[must_use]
+    pub fn stateful<P, H, E>(mut self, path: P, method: Method, handler: H) -> Self
+    where
+        P: AsRef<str>,
+        H: StateHandler<S, E>,
+    {
+        let cell = if let Some(cell) = self.states.first() {
+            cell.clone()
+        } else {
+            let cell = StateCell::new();
+            self.states.push(cell.clone());
+            cell
+        };
+        self.route(
+            path,
+            Route::new().on(method, handler.into_state_handler(cell)),
+        )
+    }
+
+    repeat!(
+        export_stateful_verb
+        stateful_get GET
+        stateful_post POST
+        stateful_put PUT
+        stateful_delete DELETE
+        stateful_head HEAD
+        stateful_options OPTIONS
+        stateful_connect CONNECT
+        stateful_patch PATCH
+        stateful_trace TRACE
+    );
+
+    /// Provides the application state for the routes, the sub-states are got from it directly
+    /// when handling the requests.
+    #[must_use]
+    pub fn with_state(self, state: S) -> Router
+    where
+        S: Clone,
+    {
+        for cell in &self.states {
+            // The cells are owned by the router, they are empty.
+            let _ = cell.set(state.clone());
+        }
+        self.into_stateless()
+    }
+
+    /// Drops the cells of the state which have been provided.
+    pub(crate) fn into_stateless(self) -> Router {
+        Router {
+            routes: self.routes,
+            error_handler: self.error_handler,
+            providers: self.providers,
+            states: Vec::new(),
+        }
+    }
+
+    /// Adds a handler with extractors with a path and HTTP verb pair, the operation is derived
+    /// from the extractors and the output of the handler.
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn api<P, H, E, O>(self, path: P, method: Method, handler: H) -> Self
+    where
+        P: AsRef<str>,
+        H: IntoHandler<Request, E> + OperationHandler<E>,
+        H::Handler: Handler<Request, Output = Result<O>> + Clone,
+        O: IntoResponse + Send + 'static,
+    {
+        self.route(path, Route::new().api(method, handler))
+    }
+
     /// Adds a handler with a path and any HTTP verbs."
     #[must_use]
-    pub fn any<S, H, O>(self, path: S, handler: H) -> Self
+    pub fn any<P, H, O>(self, path: P, handler: H) -> Self
     where
-        S: AsRef<str>,
+        P: AsRef<str>,
         H: Handler<Request, Output = Result<O>> + Clone,
         O: IntoResponse + Send + 'static,
     {
@@ -146,17 +285,12 @@ impl Router {
             routes: self.routes.map(|routes| {
                 routes
                     .into_iter()
-                    .map(|(path, route)| {
-                        (
-                            path,
-                            route
-                                .into_iter()
-                                .map(|(method, handler)| (method, f(handler)))
-                                .collect(),
-                        )
-                    })
+                    .map(|(path, route)| (path, route.map_handler(&f)))
                     .collect()
             }),
+            error_handler: self.error_handler,
+            providers: self.providers,
+            states: self.states,
         }
     }
 
@@ -178,18 +312,156 @@ impl Router {
     {
         self.map_handler(|handler| handler.around(f.clone()).boxed())
     }
+
+    /// Adds the CORS middleware for the routes, and answers the preflight requests of the
+    /// routes which have no `OPTIONS` handler, see [`Route::cors`].
+    #[cfg(feature = "cors")]
+    #[must_use]
+    pub fn cors(self, config: cors::Config) -> Self {
+        Self {
+            routes: self.routes.map(|routes| {
+                routes
+                    .into_iter()
+                    .map(|(path, route)| (path, route.cors(config.clone())))
+                    .collect()
+            }),
+            ..self
+        }
+    }
+
+    /// Sets the error handler for all routes, which wraps the other middleware.
+    #[must_use]
+    pub fn error_handler(mut self, handler: ErrorHandler) -> Self {
+        self.error_handler = Some(handler);
+        self
+    }
+
+    /// Registers the factory of a dependency for all routes, which is resolved lazily by the
+    /// [`Inject`] extractor and cached in the request.
+    ///
+    /// The dependencies of a nested router fall back to the ones of the parent router.
+    ///
+    /// [`Inject`]: viz_core::types::Inject
+    #[must_use]
+    pub fn provide<F, E>(mut self, factory: F) -> Self
+    where
+        F: Factory<E>,
+        F::Output: Clone + Send + Sync + 'static,
+        E: 'static,
+    {
+        self.providers = Some(self.providers.unwrap_or_default().provide(factory));
+        self
+    }
+
+    /// Registers the factory of a dependency with a finalizer, which runs after the handler if
+    /// the dependency is resolved, and sees the result of the handler.
+    ///
+    /// E.g. commits the transaction on success and rolls back it on error.
+    #[must_use]
+    pub fn provide_with<F, E, Fin, Fut>(mut self, factory: F, finalizer: Fin) -> Self
+    where
+        F: Factory<E>,
+        F::Output: Clone + Send + Sync + 'static,
+        E: 'static,
+        Fin: Fn(F::Output, &Result<Response>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.providers = Some(
+            self.providers
+                .unwrap_or_default()
+                .provide_with(factory, finalizer),
+        );
+        self
+    }
+
+    /// Generates the `OpenAPI` document from the routes and their operations.
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn openapi_document(&self, info: Info) -> OpenApi {
+        self.routes
+            .iter()
+            .flatten()
+            .flat_map(|(path, route)| {
+                route
+                    .methods
+                    .iter()
+                    .filter(|(method, _)| !route.is_preflight(method))
+                    .map(move |(method, _)| {
+                        (
+                            path,
+                            method,
+                            route.get_operation(method).cloned().unwrap_or_default(),
+                        )
+                    })
+            })
+            .fold(OpenApi::new(info), |doc, (path, method, operation)| {
+                doc.operation(path, method.clone(), operation)
+            })
+    }
+
+    /// Serves the `OpenAPI` document at the path, it should be called after all routes are added.
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn openapi<P>(self, path: P, info: Info) -> Self
+    where
+        P: AsRef<str>,
+    {
+        let document = Arc::new(self.openapi_document(info).to_value());
+
+        self.get(path, move |_| {
+            let document = document.clone();
+            async move { Ok(Response::json(&*document)?) }
+        })
+    }
+}
+
+/// Only the router without a state to provide can be cloned, see [`Router::with_state`].
+impl Clone for Router {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+            error_handler: self.error_handler.clone(),
+            providers: self.providers.clone(),
+            states: self.states.clone(),
+        }
+    }
+}
+
+impl<S> Default for Router<S> {
+    fn default() -> Self {
+        Self {
+            routes: None,
+            error_handler: None,
+            providers: None,
+            states: Vec::new(),
+        }
+    }
+}
+
+impl<S> fmt::Debug for Router<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field("routes", &self.routes)
+            .field("error_handler", &self.error_handler)
+            .field("providers", &self.providers)
+            .field("states", &self.states)
+            .finish()
+    }
 }
 
 #[cfg(test)]
 #[allow(clippy::unused_async)]
 mod tests {
     use http_body_util::{BodyExt, Full};
-    use std::sync::Arc;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
     use viz_core::{
         async_trait,
-        types::{Params, RouteInfo},
-        Body, Error, Handler, HandlerExt, IntoResponse, Method, Next, Request, RequestExt,
-        Response, ResponseExt, Result, StatusCode, Transform,
+        types::{FromRef, Inject, Params, RouteInfo, State},
+        Body, Error, FromRequestParts, Handler, HandlerExt, IntoHandler, IntoResponse, Method,
+        Next, Request, RequestExt, Response, ResponseExt, Result, StatusCode, Transform,
     };
 
     use crate::{any, get, Resources, Route, Router, Tree};
@@ -523,6 +795,328 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn error_handler() -> anyhow::Result<()> {
+        use std::sync::Mutex;
+
+        use crate::{ErrorContext, ErrorHandler};
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+
+        let admin = Router::new()
+            .get("/", |_: Request| async {
+                Err::<Response, _>(Error::boxed(std::fmt::Error))
+            })
+            .error_handler(ErrorHandler::new().status::<std::fmt::Error>(StatusCode::FORBIDDEN));
+
+        let router = Router::new()
+            .get("/users/:id", |req: Request| async move {
+                let id = req.param::<String>("id")?;
+                let id = id.parse::<u64>().map_err(Error::boxed)?;
+                Ok(Response::text(id.to_string()))
+            })
+            .get("/io", |_: Request| async {
+                Err::<Response, _>(Error::boxed(std::io::Error::other("disk is full")))
+            })
+            .get("/teapot", |_: Request| async {
+                Err::<Response, _>(StatusCode::IM_A_TEAPOT.into_error())
+            })
+            .nest("/admin", admin)
+            .error_handler(
+                ErrorHandler::new()
+                    .map(|e: &std::num::ParseIntError, cx: &ErrorContext| {
+                        assert_eq!(cx.method, Method::GET);
+                        assert_eq!(
+                            cx.route.as_ref().map(|info| info.pattern.as_str()),
+                            Some("/users/:id")
+                        );
+                        Some((StatusCode::NOT_FOUND, e.to_string()))
+                    })
+                    .report({
+                        let reports = reports.clone();
+                        move |_, cx| reports.lock().unwrap().push(cx.path.clone())
+                    })
+                    .production(true),
+            );
+
+        let tree: Tree = router.into();
+
+        let call = |method: Method, path: &'static str| {
+            let (mut req, method, path) = client(method, path);
+            let (h, route) = tree.find(&method, &path).unwrap();
+            req.extensions_mut().insert(Arc::from(RouteInfo {
+                id: *route.id,
+                pattern: route.pattern(),
+                params: route.params().into(),
+            }));
+            h.call(req)
+        };
+
+        let resp = call(Method::GET, "/users/7").await?;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = call(Method::GET, "/users/viz").await?;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = call(Method::GET, "/io").await?;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.into_body().collect().await?.to_bytes(), "");
+        assert_eq!(*reports.lock().unwrap(), ["/io"]);
+
+        let resp = call(Method::GET, "/teapot").await?;
+        assert_eq!(resp.status(), StatusCode::IM_A_TEAPOT);
+
+        let resp = call(Method::GET, "/admin").await?;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(reports.lock().unwrap().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_state() -> anyhow::Result<()> {
+        #[derive(Clone)]
+        struct AppState {
+            db: Db,
+            name: &'static str,
+        }
+
+        #[derive(Clone)]
+        struct Db(u64);
+
+        impl FromRef<AppState> for Db {
+            fn from_ref(state: &AppState) -> Self {
+                state.db.clone()
+            }
+        }
+
+        async fn count(State(db): State<Db>) -> String {
+            db.0.to_string()
+        }
+
+        async fn show(
+            State(state): State<AppState>,
+            Params(id): Params<u64>,
+            req: Request,
+        ) -> Result<String> {
+            Ok(format!("{} {} {}", state.name, id + state.db.0, req.path()))
+        }
+
+        let users = Router::default().stateful_get("/:id", show);
+        let router = Router::default()
+            .stateful("/count", Method::GET, count)
+            .nest("/users", users);
+
+        let router = router.with_state(AppState {
+            db: Db(7),
+            name: "viz",
+        });
+
+        let tree: Tree = router.into();
+
+        let call = |method: Method, path: &'static str| {
+            let (mut req, method, path) = client(method, path);
+            let (h, route) = tree.find(&method, &path).unwrap();
+            req.extensions_mut().insert(Arc::from(RouteInfo {
+                id: *route.id,
+                pattern: route.pattern(),
+                params: route.params().into(),
+            }));
+            h.call(req)
+        };
+
+        let resp = call(Method::GET, "/count").await?;
+        assert_eq!(resp.into_body().collect().await?.to_bytes(), "7");
+
+        let resp = call(Method::GET, "/users/1").await?;
+        assert_eq!(
+            resp.into_body().collect().await?.to_bytes(),
+            "viz 8 /users/1"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn providers() -> anyhow::Result<()> {
+        #[derive(Clone)]
+        struct Db(Arc<Mutex<Vec<&'static str>>>);
+
+        #[derive(Clone)]
+        struct Tx(Db);
+
+        #[derive(Clone)]
+        struct Name(&'static str);
+
+        async fn create(Inject(db): Inject<Db>, Inject(tx): Inject<Tx>) -> Result<&'static str> {
+            db.0.lock().unwrap().push("create");
+            tx.0 .0.lock().unwrap().push("insert");
+            Ok("created")
+        }
+
+        async fn delete(Inject(_): Inject<Tx>) -> Result<&'static str> {
+            Err(StatusCode::BAD_REQUEST.into_error())
+        }
+
+        async fn name(Inject(name): Inject<Name>, Inject(_): Inject<Db>) -> Result<&'static str> {
+            Ok(name.0)
+        }
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let users = Router::new()
+            .get("/", name.into_handler())
+            .provide(|| async { Ok(Name("users")) });
+        let router = Router::new()
+            .post("/", create.into_handler())
+            .delete("/", delete.into_handler())
+            .get("/name", name.into_handler())
+            .nest("/users", users)
+            .provide({
+                let log = log.clone();
+                let count = count.clone();
+                move || {
+                    count.fetch_add(1, Ordering::SeqCst);
+                    let db = Db(log.clone());
+                    async move { Ok(db) }
+                }
+            })
+            .provide_with(
+                |Inject(db): Inject<Db>| async move { Ok(Tx(db)) },
+                |tx: Tx, result: &Result<Response>| {
+                    let end = if result.is_ok() { "commit" } else { "rollback" };
+                    tx.0 .0.lock().unwrap().push(end);
+                    async { Ok(()) }
+                },
+            );
+
+        let tree: Tree = router.into();
+
+        let call = |method: Method, path: &'static str| {
+            let (req, method, path) = client(method, path);
+            let (h, _) = tree.find(&method, &path).unwrap();
+            h.call(req)
+        };
+
+        let resp = call(Method::POST, "/").await?;
+        assert_eq!(resp.into_body().collect().await?.to_bytes(), "created");
+        assert_eq!(*log.lock().unwrap(), ["create", "insert", "commit"]);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        assert!(call(Method::DELETE, "/").await.is_err());
+        assert_eq!(log.lock().unwrap()[3..], ["rollback"]);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        let resp = call(Method::GET, "/users").await?;
+        assert_eq!(resp.into_body().collect().await?.to_bytes(), "users");
+        assert_eq!(log.lock().unwrap().len(), 4);
+
+        let Err(e) = call(Method::GET, "/name").await else {
+            panic!("the provider of `Name` should be missing");
+        };
+        assert_eq!(
+            e.into_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn providers_cycle() -> anyhow::Result<()> {
+        #[derive(Clone)]
+        struct Node(u8);
+
+        #[derive(Clone)]
+        struct Ping;
+
+        #[derive(Clone)]
+        struct Pong;
+
+        async fn node(Inject(node): Inject<Node>) -> Result<String> {
+            Ok(node.0.to_string())
+        }
+
+        async fn ping(Inject(_): Inject<Ping>) -> Result<&'static str> {
+            Ok("ping")
+        }
+
+        let router = Router::new()
+            .get("/node", node.into_handler())
+            .get("/ping", ping.into_handler())
+            .provide(|Inject(node): Inject<Node>| async move { Ok(Node(node.0 + 1)) })
+            .provide(|Inject(_): Inject<Pong>| async { Ok(Ping) })
+            .provide(|Inject(_): Inject<Ping>| async { Ok(Pong) });
+
+        let tree: Tree = router.into();
+
+        for path in ["/node", "/ping"] {
+            let (req, method, path) = client(Method::GET, path);
+            let (h, _) = tree.find(&method, &path).unwrap();
+            let Err(e) = h.call(req).await else {
+                panic!("the dependencies of `{path}` are cyclic");
+            };
+            let resp = e.into_response();
+            assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert!(
+                std::str::from_utf8(&resp.into_body().collect().await?.to_bytes())?
+                    .starts_with("cyclic dependency")
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn providers_cycle_concurrent() -> anyhow::Result<()> {
+        #[derive(Clone)]
+        struct Ping;
+
+        #[derive(Clone)]
+        struct Pong;
+
+        /// Extracts `Pong` after yielding, so `Pong` is being resolved by the other extractor.
+        struct Later(Pong);
+
+        impl FromRequestParts for Later {
+            type Error = Error;
+
+            async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+                tokio::task::yield_now().await;
+                let Inject(pong) = Inject::<Pong>::extract_parts(req).await?;
+                Ok(Self(pong))
+            }
+        }
+
+        async fn both(req: Request) -> Result<&'static str> {
+            let (Inject(Ping), Inject(Pong)) =
+                <(Inject<Ping>, Inject<Pong>)>::extract_parts(&req).await?;
+            Ok("ping pong")
+        }
+
+        let router = Router::new()
+            .get("/", both.into_handler())
+            .provide(|Later(_): Later| async { Ok(Ping) })
+            .provide(|Inject(_): Inject<Ping>| async { Ok(Pong) });
+
+        let tree: Tree = router.into();
+
+        let (req, method, path) = client(Method::GET, "/");
+        let (h, _) = tree.find(&method, &path).unwrap();
+        let Err(e) = h.call(req).await else {
+            panic!("the dependencies are cyclic");
+        };
+        let resp = e.into_response();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(
+            std::str::from_utf8(&resp.into_body().collect().await?.to_bytes())?
+                .starts_with("cyclic dependency")
+        );
+
+        Ok(())
+    }
+
     fn client(method: Method, path: &str) -> (Request, Method, String) {
         (
             Request::builder()
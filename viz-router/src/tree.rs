@@ -44,9 +44,21 @@ impl AsMut<Vec<(Method, PathTree<BoxHandler>)>> for Tree {
 
 impl From<Router> for Tree {
     fn from(router: Router) -> Self {
+        // The unit state is the same for the clones of the router, a provided cell is fine.
+        for cell in &router.states {
+            let _ = cell.set(());
+        }
+        let mut router = router.into_stateless();
+        if let Some(providers) = router.providers.take() {
+            router = router.with(providers);
+        }
+        if let Some(handler) = router.error_handler.take() {
+            router = router.with(handler);
+        }
+
         let mut tree = Tree::default();
         if let Some(routes) = router.routes {
-            for (mut path, Route { methods }) in routes {
+            for (mut path, Route { methods, .. }) in routes {
                 if !path.starts_with('/') {
                     path.insert(0, '/');
                 }
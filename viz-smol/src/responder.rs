@@ -42,7 +42,9 @@ where
             return Box::pin(async move { Ok(StatusCode::NOT_FOUND.into_response()) });
         };
 
-        req.extensions_mut().insert(self.remote_addr.clone());
+        if let Some(remote_addr) = self.remote_addr.clone() {
+            req.extensions_mut().insert(remote_addr);
+        }
         req.extensions_mut()
             .insert(Arc::from(crate::types::RouteInfo {
                 id: *route.id,
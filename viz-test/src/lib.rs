@@ -57,4 +57,8 @@ impl TestServer {
     pub fn put(&self, url: impl AsRef<str>) -> RequestBuilder {
         self.client.put(self.path(url))
     }
+
+    pub fn options(&self, url: impl AsRef<str>) -> RequestBuilder {
+        self.client.request(http::Method::OPTIONS, self.path(url))
+    }
 }
@@ -0,0 +1,176 @@
+use viz::{
+    types::{Params, Query},
+    Body, Error, IntoHandler, IntoResponse, Request, RequestExt, Result, StatusCode,
+};
+
+#[tokio::test]
+async fn handler_extractors() -> Result<()> {
+    use futures_util::TryStreamExt;
+    use serde::Deserialize;
+    use viz::Router;
+    use viz_test::TestServer;
+
+    #[derive(Deserialize)]
+    struct Page {
+        p: u8,
+    }
+
+    async fn upload(Params(id): Params<u64>, mut req: Request) -> Result<String> {
+        let size = req.bytes().await?.len();
+        Ok(format!("{id}:{size}"))
+    }
+
+    async fn stream(Params(id): Params<u64>, body: Body) -> Result<String> {
+        let size = body
+            .try_fold(0, |size, chunk| async move { Ok(size + chunk.len()) })
+            .await?;
+        Ok(format!("{id}:{size}"))
+    }
+
+    async fn page(Query(page): Query<Page>) -> String {
+        format!("page {}", page.p)
+    }
+
+    async fn created(req: Request) -> impl IntoResponse {
+        (StatusCode::CREATED, req.uri().path().to_string())
+    }
+
+    async fn missing(Params(id): Params<u64>) -> Result<String> {
+        if id == 0 {
+            Err(StatusCode::NOT_FOUND.into_error())?;
+        }
+        Ok(format!("NO.{id}"))
+    }
+
+    let router = Router::new()
+        .post("/upload/:id", upload.into_handler())
+        .post("/stream/:id", stream.into_handler())
+        .get("/page", page.into_handler())
+        .get("/created", created.into_handler())
+        .get("/missing/:id", missing.into_handler());
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client
+        .post("/upload/1")
+        .body("viz")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "1:3");
+
+    let resp = client
+        .post("/upload/x")
+        .body("viz")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 500);
+
+    let resp = client
+        .post("/stream/2")
+        .body("web")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "2:3");
+
+    let resp = client.get("/page?p=3").send().await.map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "page 3");
+
+    let resp = client.get("/created").send().await.map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 201);
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "/created");
+
+    let resp = client
+        .get("/missing/0")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn handler_parts_extractors() -> Result<()> {
+    use serde::Deserialize;
+    use viz::{
+        types::{Json, State},
+        FromRequestParts, HandlerExt, Router,
+    };
+    use viz_test::TestServer;
+
+    #[derive(Deserialize)]
+    struct Page {
+        p: u8,
+    }
+
+    #[derive(Deserialize)]
+    struct Todo {
+        text: String,
+    }
+
+    struct Token(String);
+
+    impl FromRequestParts for Token {
+        type Error = StatusCode;
+
+        async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
+            req.header::<_, String>("x-token")
+                .map(Self)
+                .ok_or(StatusCode::UNAUTHORIZED)
+        }
+    }
+
+    async fn create(
+        Token(token): Token,
+        Params(id): Params<u64>,
+        Query(page): Query<Page>,
+        State(name): State<String>,
+        Json(todo): Json<Todo>,
+    ) -> String {
+        format!("{token}:{id}:{}:{name}:{}", page.p, todo.text)
+    }
+
+    async fn show(req: Request) -> Result<String> {
+        let (Token(token), Params(id)) = req.extract_parts::<(Token, Params<u64>)>().await?;
+        Ok(format!("{token}:{id}"))
+    }
+
+    let router = Router::new()
+        .post(
+            "/todos/:id",
+            create.into_handler().with(State::new("viz".to_string())),
+        )
+        .get("/todos/:id", show);
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client
+        .post("/todos/1?p=2")
+        .header("x-token", "t")
+        .json(&serde_json::json!({ "text": "write" }))
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "t:1:2:viz:write");
+
+    let resp = client
+        .post("/todos/1?p=2")
+        .json(&serde_json::json!({ "text": "write" }))
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 401);
+
+    let resp = client
+        .get("/todos/3")
+        .header("x-token", "t")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "t:3");
+
+    Ok(())
+}
@@ -0,0 +1,601 @@
+use serde::Deserialize;
+use viz::{
+    types::{Form, Multipart},
+    Error, IntoResponse, Request, RequestExt, Result, Router,
+};
+
+#[derive(Debug, Deserialize)]
+struct User {
+    name: String,
+}
+
+#[tokio::test]
+async fn middleware_csrf() -> Result<()> {
+    use futures_util::TryStreamExt;
+    use viz::middleware::{
+        cookie,
+        csrf::{self, CsrfToken},
+    };
+    use viz_test::http::{
+        header::{CONTENT_TYPE, COOKIE, ORIGIN, SET_COOKIE},
+        StatusCode,
+    };
+    use viz_test::{multipart, TestServer};
+
+    let router = Router::new()
+        .get("/", |mut req: Request| async move {
+            Ok(req.extract::<CsrfToken>().await?.0)
+        })
+        .post("/form", |mut req: Request| async move {
+            let Form(user) = req.extract::<Form<User>>().await?;
+            Ok(user.name)
+        })
+        .post("/multipart", |mut req: Request| async move {
+            let mut form = req.extract::<Multipart>().await?;
+            let mut names = Vec::new();
+            while let Some(mut field) = form.try_next().await? {
+                field.ignore().await?;
+                names.push(field.name);
+            }
+            Ok(names.join(","))
+        })
+        .with(
+            csrf::DefaultConfig::default()
+                .form_field("_csrf")
+                .same_origin(true),
+        )
+        .with(cookie::Config::default());
+
+    let client = TestServer::new(router).await?;
+    let origin = format!("http://{}", client.addr());
+
+    let resp = client.get("/").send().await.map_err(Error::boxed)?;
+    let cookie = resp.headers().get(SET_COOKIE).cloned().unwrap();
+    let token = resp.text().await.map_err(Error::boxed)?;
+
+    // missing token
+    let resp = client
+        .post("/form")
+        .header(COOKIE, cookie.clone())
+        .header(ORIGIN, origin.clone())
+        .form(&[("name", "viz")])
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // cross origin
+    let resp = client
+        .post("/form")
+        .header(COOKIE, cookie.clone())
+        .header(ORIGIN, "http://example.com")
+        .form(&[("_csrf", token.as_str()), ("name", "viz")])
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = client
+        .post("/form")
+        .header(COOKIE, cookie.clone())
+        .header(ORIGIN, origin.clone())
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(format!("_csrf={token}&name=viz"))
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "viz");
+
+    // the default port and the forwarded host
+    for (host, origin) in [
+        ("viz.rs", "http://viz.rs:80"),
+        ("viz.rs:443", "https://VIZ.rs"),
+        ("viz.rs", "https://viz.rs:8443"),
+    ] {
+        let resp = client
+            .post("/form")
+            .header(COOKIE, cookie.clone())
+            .header(ORIGIN, origin)
+            .header("x-forwarded-host", host)
+            .form(&[("_csrf", token.as_str()), ("name", "viz")])
+            .send()
+            .await
+            .map_err(Error::boxed)?;
+        let status = if origin.ends_with(":8443") {
+            StatusCode::FORBIDDEN
+        } else {
+            StatusCode::OK
+        };
+        assert_eq!(resp.status(), status);
+    }
+
+    let form = multipart::Form::new()
+        .text("_csrf", token.clone())
+        .text("name", "viz");
+    let resp = client
+        .post("/multipart")
+        .header(COOKIE, cookie)
+        .header(ORIGIN, origin)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "_csrf,name");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn middleware_cors() -> Result<()> {
+    use viz::middleware::cors::{self, Any};
+    use viz_test::http::{
+        header::{
+            ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_ORIGIN,
+            ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ALLOW, ORIGIN,
+        },
+        StatusCode,
+    };
+    use viz_test::TestServer;
+
+    let router = Router::new().get("/", |_| async { Ok("public") }).route(
+        "/api",
+        viz::get(|_| async { Ok("api") }).cors(
+            cors::Config::default()
+                .allow_origins(["https://*.viz.rs"])
+                .allow_headers(Any)
+                .private_network(true),
+        ),
+    );
+
+    let client = TestServer::new(router).await?;
+
+    // no `OPTIONS` route and no CORS middleware
+    let resp = client
+        .options("/")
+        .header(ORIGIN, "https://app.viz.rs")
+        .header(ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = client
+        .options("/api")
+        .header(ORIGIN, "https://app.viz.rs")
+        .header(ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .header(ACCESS_CONTROL_REQUEST_HEADERS, "x-token, content-type")
+        .header("access-control-request-private-network", "true")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        resp.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+        "https://app.viz.rs"
+    );
+    assert_eq!(
+        resp.headers().get(ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+        "x-token, content-type"
+    );
+    assert_eq!(
+        resp.headers()
+            .get("access-control-allow-private-network")
+            .unwrap(),
+        "true"
+    );
+
+    let resp = client
+        .options("/api")
+        .header(ORIGIN, "https://viz.rs.evil.com")
+        .header(ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // not a preflight request
+    let resp = client.options("/api").send().await.map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert_eq!(resp.headers().get(ALLOW).unwrap(), "GET, OPTIONS");
+
+    let resp = client
+        .get("/api")
+        .header(ORIGIN, "https://app.viz.rs")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+        "https://app.viz.rs"
+    );
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "api");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn middleware_ip_filter() -> Result<()> {
+    use viz::middleware::ip_filter::{self, Cidr};
+    use viz_test::http::StatusCode;
+    use viz_test::TestServer;
+
+    let internal = ip_filter::Config::new().allow(["10.0.0.0/8".parse::<Cidr>().unwrap()]);
+    let handle = internal.handle();
+
+    let router = Router::new()
+        .get("/", |_| async { Ok("public") })
+        .nest(
+            "/admin",
+            Router::new()
+                .get("/", |_| async { Ok("admin") })
+                .get("/users", |_| async { Ok("users") })
+                .with(internal),
+        )
+        .nest(
+            "/proxied",
+            Router::new().get("/", |_| async { Ok("proxied") }).with(
+                ip_filter::Config::new()
+                    .deny(["192.168.0.0/16".parse::<Cidr>().unwrap()])
+                    .realip(true),
+            ),
+        );
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client.get("/").send().await.map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = client.get("/admin").send().await.map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = client
+        .get("/admin/users")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // reloads at runtime
+    handle.allow([
+        "10.0.0.0/8".parse::<Cidr>().unwrap(),
+        "127.0.0.0/8".parse().unwrap(),
+    ]);
+    let resp = client
+        .get("/admin/users")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "users");
+
+    let resp = client
+        .get("/proxied")
+        .header("x-real-ip", "192.168.1.1")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = client
+        .get("/proxied")
+        .header("x-real-ip", "::1")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn middleware_request_id() -> Result<()> {
+    use viz::{
+        middleware::request_id::{self, RequestId},
+        BoxHandler, Handler, Next, Response,
+    };
+    use viz_test::http::StatusCode;
+    use viz_test::TestServer;
+
+    // The typed error is still seen by the outer middleware.
+    async fn teapot((req, h): Next<Request, BoxHandler>) -> Result<Response> {
+        match h.call(req).await {
+            Err(Error::Report(e, mut resp)) if e.is::<std::io::Error>() => {
+                *resp.status_mut() = viz::StatusCode::IM_A_TEAPOT;
+                Err(Error::Report(e, resp))
+            }
+            res => res,
+        }
+    }
+
+    let router = Router::new()
+        .get("/", |mut req: Request| async move {
+            Ok(req.extract::<RequestId>().await?.0)
+        })
+        .get("/error", |_| async {
+            Err::<(), _>(viz::StatusCode::BAD_REQUEST.into_error())
+        })
+        .get("/boxed", |_| async {
+            Err::<(), _>(Error::boxed(std::io::Error::other("boxed")))
+        })
+        .with(request_id::Config::new().generator(request_id::ulid))
+        .with_handler(teapot);
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client.get("/").send().await.map_err(Error::boxed)?;
+    let id = resp.headers().get("x-request-id").cloned().unwrap();
+    assert_eq!(id.len(), 26);
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, id);
+
+    let resp = client
+        .get("/")
+        .header("x-request-id", "abc-123")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "abc-123");
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "abc-123");
+
+    // invalid request ID is replaced
+    let resp = client
+        .get("/")
+        .header("x-request-id", "a".repeat(200))
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.headers().get("x-request-id").unwrap().len(), 26);
+
+    let resp = client
+        .get("/error")
+        .header("x-request-id", "abc-123")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "abc-123");
+
+    let resp = client
+        .get("/boxed")
+        .header("x-request-id", "abc-123")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::IM_A_TEAPOT);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "abc-123");
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn middleware_request_id_production() -> Result<()> {
+    use viz::{middleware::request_id, ErrorHandler};
+    use viz_test::http::StatusCode;
+    use viz_test::TestServer;
+
+    let router = Router::new()
+        .get("/boxed", |_| async {
+            Err::<(), _>(Error::boxed(std::io::Error::other("/var/db is corrupted")))
+        })
+        .get("/report", |_| async {
+            Err::<(), _>(Error::Report(
+                std::io::Error::other("upstream").into(),
+                (viz::StatusCode::BAD_GATEWAY, "upstream 10.0.0.1 is down").into_response(),
+            ))
+        })
+        .with(request_id::Config::new())
+        .error_handler(ErrorHandler::new().production(true));
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client
+        .get("/boxed")
+        .header("x-request-id", "abc-123")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "abc-123");
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "");
+
+    let resp = client.get("/report").send().await.map_err(Error::boxed)?;
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(resp.headers().contains_key("x-request-id"));
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn middleware_logger() -> Result<()> {
+    use std::sync::{Arc, Mutex};
+    use viz::{
+        middleware::{
+            logger::{self, Format},
+            request_id,
+        },
+        types::Cidr,
+    };
+    use viz_test::TestServer;
+
+    let lines = Arc::new(Mutex::new(Vec::<String>::new()));
+
+    let router = Router::new()
+        .get("/health", |_| async { Ok("ok") })
+        .get("/users/:id", |_| async { Ok("user") })
+        .post("/users", |_| async {
+            Err::<(), _>(viz::StatusCode::UNPROCESSABLE_ENTITY.into_error())
+        })
+        .with(
+            logger::Config::new()
+                .format(Format::Json)
+                .writer({
+                    let lines = lines.clone();
+                    move |_, line| lines.lock().unwrap().push(line.to_string())
+                })
+                .skip(|req| req.path() == "/health")
+                .trusted_proxies(["10.0.0.0/8".parse::<Cidr>().unwrap()]),
+        )
+        .with(request_id::Config::new());
+
+    let client = TestServer::new(router).await?;
+
+    client.get("/health").send().await.map_err(Error::boxed)?;
+    assert!(lines.lock().unwrap().is_empty());
+
+    client
+        .get("/users/1?tab=profile")
+        .header("user-agent", "viz-test")
+        .header("x-request-id", "abc-123")
+        .header("x-forwarded-for", "1.1.1.1")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    client
+        .post("/users")
+        .body("viz")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+
+    let lines = lines.lock().unwrap();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains(r#""method":"GET""#));
+    assert!(lines[0].contains(r#""uri":"/users/1?tab=profile""#));
+    assert!(lines[0].contains(r#""route":"/users/:id""#));
+    assert!(lines[0].contains(r#""status":200"#));
+    assert!(lines[0].contains(r#""bytes_out":4"#));
+    assert!(lines[0].contains(r#""realip":"127.0.0.1""#));
+    assert!(lines[0].contains(r#""user_agent":"viz-test""#));
+    assert!(lines[0].contains(r#""request_id":"abc-123""#));
+    assert!(lines[1].contains(r#""status":422"#));
+    assert!(lines[1].contains(r#""bytes_in":3"#));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn middleware_problem() -> Result<()> {
+    use viz::{
+        middleware::problem, types::Problem, BoxHandler, Handler, Next, Response, StatusCode,
+    };
+    use viz_test::TestServer;
+
+    // The source of the error is still seen by the outer middleware.
+    async fn reported((req, h): Next<Request, BoxHandler>) -> Result<Response> {
+        match h.call(req).await {
+            Err(Error::Report(e, mut resp)) if e.is::<std::io::Error>() => {
+                resp.headers_mut()
+                    .insert("x-reported", e.to_string().parse().unwrap());
+                Err(Error::Report(e, resp))
+            }
+            res => res,
+        }
+    }
+
+    let router = Router::new()
+        .get("/users", |req: Request| async move {
+            Ok(req.query::<User>()?.name)
+        })
+        .get("/boom", |_| async {
+            Err::<(), _>(Error::boxed(std::io::Error::other("database is down")))
+        })
+        .get("/conflict", |_| async {
+            Err::<(), _>(
+                Problem::new(StatusCode::CONFLICT)
+                    .detail("user already exists")
+                    .extension("user_id", 7)
+                    .into_error(),
+            )
+        })
+        .with(problem::Config::new().type_base("https://viz.rs/problems/"))
+        .with_handler(reported);
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client
+        .get("/users?name=viz")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 200);
+
+    let resp = client.get("/users").send().await.map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/problem+json"
+    );
+    let body = resp
+        .json::<serde_json::Value>()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(body["type"], "https://viz.rs/problems/400");
+    assert_eq!(body["title"], "Bad Request");
+    assert_eq!(body["status"], 400);
+    assert!(body["detail"].is_string());
+    assert_eq!(body["instance"], "/users");
+
+    let resp = client.get("/boom").send().await.map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 500);
+    assert_eq!(
+        resp.headers().get("x-reported").unwrap(),
+        "database is down"
+    );
+    let body = resp
+        .json::<serde_json::Value>()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(body["title"], "Internal Server Error");
+    assert!(body.get("detail").is_none());
+
+    let resp = client
+        .get("/conflict")
+        .header("accept", "text/html")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 409);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/problem+json"
+    );
+    let body = resp
+        .json::<serde_json::Value>()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(body["detail"], "user already exists");
+    assert_eq!(body["user_id"], 7);
+
+    let resp = client
+        .get("/users")
+        .header("accept", "text/html,*/*;q=0.8")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    assert!(resp
+        .text()
+        .await
+        .map_err(Error::boxed)?
+        .contains("<h1>400 Bad Request</h1>"));
+
+    let resp = client
+        .get("/boom")
+        .header("accept", "text/plain")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 500);
+    assert_eq!(
+        resp.text().await.map_err(Error::boxed)?,
+        "Internal Server Error"
+    );
+
+    Ok(())
+}
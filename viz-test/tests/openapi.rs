@@ -0,0 +1,143 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use viz::{
+    get,
+    handlers::openapi::{Redoc, SwaggerUi},
+    middleware::cors,
+    openapi::{Info, Operation},
+    types::{Json, Params, Query},
+    Method, Request, Result, Route, Router, StatusCode,
+};
+use viz_test::TestServer;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+struct Page {
+    offset: Option<u32>,
+}
+
+async fn list(_: Query<Page>) -> Result<Json<Vec<User>>> {
+    Ok(Json(Vec::new()))
+}
+
+async fn create(Json(user): Json<User>) -> Result<Json<User>> {
+    Ok(Json(user))
+}
+
+async fn show(Params(id): Params<u64>) -> Result<Json<User>> {
+    Ok(Json(User {
+        id,
+        name: String::new(),
+    }))
+}
+
+async fn files(Params((name, ext)): Params<(String, String)>, _: Request) -> Result<String> {
+    Ok(format!("{name}.{ext}"))
+}
+
+#[tokio::test]
+async fn openapi() -> Result<()> {
+    let router = Router::new()
+        .route(
+            "/users",
+            Route::new()
+                .api(Method::GET, list)
+                .describe(Method::GET, |operation| {
+                    operation.summary("List users").tag("users")
+                })
+                .api(Method::POST, create)
+                .describe(Method::POST, |operation| {
+                    operation.status(StatusCode::CREATED, "Created")
+                }),
+        )
+        .route(
+            "/users/:id",
+            Route::new()
+                .api(Method::GET, show)
+                .describe(Method::GET, |operation| {
+                    operation.status(StatusCode::NOT_FOUND, "Not Found")
+                }),
+        )
+        .api("/files/:name.:ext", Method::GET, files)
+        .route(
+            "/health",
+            get(|_| async { Ok("ok") }).cors(cors::Config::default()),
+        )
+        .route(
+            "/internal",
+            get(|_| async { Ok(()) }).operation(Method::GET, Operation::new().hidden()),
+        )
+        .get("/docs", SwaggerUi::new("/openapi.json"))
+        .get("/redoc", Redoc::new("/openapi.json"))
+        .get(
+            "/unsafe",
+            SwaggerUi::new("/\"</script><script>alert(1)</script>"),
+        )
+        .openapi("/openapi.json", Info::new("Users", "1.0.0"));
+
+    let client = TestServer::new(router).await.unwrap();
+
+    let resp = client.get("/openapi.json").send().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let doc = resp.json::<serde_json::Value>().await.unwrap();
+
+    assert_eq!(doc["openapi"], "3.1.0");
+    assert_eq!(doc["info"]["title"], "Users");
+
+    let list = &doc["paths"]["/users"]["get"];
+    assert_eq!(list["summary"], "List users");
+    assert_eq!(list["tags"][0], "users");
+    assert_eq!(list["parameters"][0]["name"], "offset");
+    assert_eq!(list["parameters"][0]["in"], "query");
+    assert!(list["responses"]["200"]["content"]["application/json"]["schema"].is_object());
+
+    let create = &doc["paths"]["/users"]["post"];
+    assert_eq!(
+        create["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/User"
+    );
+    assert_eq!(create["responses"]["201"]["description"], "Created");
+
+    let show = &doc["paths"]["/users/{id}"]["get"];
+    assert_eq!(show["parameters"][0]["name"], "id");
+    assert_eq!(show["parameters"][0]["in"], "path");
+    assert_eq!(show["parameters"][0]["schema"]["type"], "integer");
+    assert_eq!(show["responses"]["404"]["description"], "Not Found");
+
+    let files = &doc["paths"]["/files/{name}.{ext}"]["get"];
+    assert_eq!(files["parameters"][0]["name"], "name");
+    assert_eq!(files["parameters"][0]["schema"]["type"], "string");
+    assert_eq!(files["parameters"][1]["name"], "ext");
+    assert_eq!(files["parameters"].as_array().map(Vec::len), Some(2));
+    assert!(files["responses"]["200"]["content"]["text/plain; charset=utf-8"].is_object());
+
+    assert!(doc["paths"]["/health"]["get"].is_object());
+    assert!(doc["paths"]["/health"]["options"].is_null());
+    assert!(doc["paths"]["/internal"].is_null());
+    assert!(doc["components"]["schemas"]["User"].is_object());
+
+    let resp = client.get("/docs").send().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(resp
+        .text()
+        .await
+        .unwrap()
+        .contains(r#"url: "/openapi.json""#));
+
+    let resp = client.get("/redoc").send().await.unwrap();
+    let html = resp.text().await.unwrap();
+    assert!(html.contains(r#"<redoc spec-url="/openapi.json">"#));
+    assert!(!html.contains("latest"));
+
+    let resp = client.get("/unsafe").send().await.unwrap();
+    let html = resp.text().await.unwrap();
+    assert!(!html.contains("</script><script>"));
+    assert!(html.contains(r#"url: "/\"\u003c/script\u003e"#));
+
+    Ok(())
+}
@@ -302,3 +302,572 @@ async fn request_session() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn request_valid() -> Result<()> {
+    use validator::Validate;
+    use viz::{
+        types::{Json, Query, Valid},
+        Router,
+    };
+    use viz_test::TestServer;
+
+    #[derive(Debug, Deserialize, Serialize, Validate)]
+    struct Address {
+        #[validate(length(min = 1, message = "city is required"))]
+        city: String,
+    }
+
+    #[derive(Debug, Deserialize, Serialize, Validate)]
+    struct User {
+        #[validate(length(min = 2, max = 8))]
+        name: String,
+        #[validate(range(min = 18))]
+        age: u8,
+        #[validate(nested)]
+        address: Address,
+    }
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Search {
+        #[validate(range(max = 100))]
+        limit: u32,
+    }
+
+    let router = Router::new()
+        .post("/users", |mut req: Request| async move {
+            let Valid(Json(user)) = req.extract::<Valid<Json<User>>>().await?;
+            Ok(Response::json(user))
+        })
+        .get("/search", |mut req: Request| async move {
+            let Valid(Query(search)) = req.extract::<Valid<Query<Search>>>().await?;
+            Ok(search.limit.to_string())
+        });
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client
+        .post("/users")
+        .json(&serde_json::json!({"name": "viz", "age": 18, "address": {"city": "Rust"}}))
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .post("/users")
+        .json(&serde_json::json!({"name": "v", "age": 17, "address": {"city": ""}}))
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 422);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    let body = resp
+        .json::<serde_json::Value>()
+        .await
+        .map_err(Error::boxed)?;
+    let errors = body["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0]["field"], "address.city");
+    assert_eq!(errors[0]["message"], "city is required");
+    assert_eq!(errors[1]["field"], "age");
+    assert_eq!(errors[1]["code"], "range");
+    assert_eq!(errors[2]["field"], "name");
+    assert_eq!(errors[2]["code"], "length");
+
+    let resp = client
+        .post("/users")
+        .json(&serde_json::json!({"name": "viz"}))
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .get("/search?limit=10")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "10");
+
+    let resp = client
+        .get("/search?limit=1000")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 422);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_msgpack_cbor() -> Result<()> {
+    use viz::{
+        middleware::limits,
+        types::{Cbor, Limits, MsgPack},
+        Router,
+    };
+    use viz_test::TestServer;
+
+    let router = Router::new()
+        .post("/msgpack", |mut req: Request| async move {
+            let MsgPack(page) = req.extract::<MsgPack<Page>>().await?;
+            Ok(MsgPack(Page { p: page.p + 1 }))
+        })
+        .post("/cbor", |mut req: Request| async move {
+            let Cbor(page) = req.extract::<Cbor<Page>>().await?;
+            Ok(Cbor(Page { p: page.p + 1 }))
+        })
+        .with(limits::Config::new().limits(Limits::new().set("msgpack", 8).set("cbor", 8).sort()));
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client
+        .post("/msgpack")
+        .header("content-type", "application/msgpack")
+        .body(rmp_serde::to_vec_named(&Page { p: 1 }).unwrap())
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/msgpack"
+    );
+    let bytes = resp.bytes().await.map_err(Error::boxed)?;
+    assert_eq!(
+        rmp_serde::from_slice::<Page>(&bytes).unwrap(),
+        Page { p: 2 }
+    );
+
+    let resp = client
+        .post("/msgpack")
+        .header("content-type", "application/json")
+        .body(r#"{"p":1}"#)
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 415);
+
+    let resp = client
+        .post("/msgpack")
+        .header("content-type", "application/x-msgpack")
+        .body(vec![0xc1])
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .post("/msgpack")
+        .header("content-type", "application/msgpack")
+        .body(vec![0; 16])
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 413);
+
+    let mut body = Vec::new();
+    ciborium::into_writer(&Page { p: 1 }, &mut body).unwrap();
+    let resp = client
+        .post("/cbor")
+        .header("content-type", "application/cbor")
+        .body(body)
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/cbor"
+    );
+    let bytes = resp.bytes().await.map_err(Error::boxed)?;
+    assert_eq!(
+        ciborium::from_reader::<Page, _>(bytes.as_ref()).unwrap(),
+        Page { p: 2 }
+    );
+
+    let resp = client
+        .post("/cbor")
+        .header("content-type", "application/cbor")
+        .body(vec![0; 16])
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 413);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_protobuf() -> Result<()> {
+    use prost::Message;
+    use viz::{
+        middleware::limits,
+        types::{Limits, Protobuf},
+        Router,
+    };
+    use viz_test::TestServer;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Item {
+        #[prost(uint32, tag = "1")]
+        id: u32,
+        #[prost(string, tag = "2")]
+        name: String,
+    }
+
+    let router = Router::new()
+        .post("/items", |mut req: Request| async move {
+            let Protobuf(mut item) = req.extract::<Protobuf<Item>>().await?;
+            item.id += 1;
+            Ok(Protobuf(item))
+        })
+        .with(limits::Config::new().limits(Limits::new().set("protobuf", 32).sort()));
+
+    let client = TestServer::new(router).await?;
+
+    let item = Item {
+        id: 1,
+        name: "viz".to_string(),
+    };
+    let resp = client
+        .post("/items")
+        .header("content-type", "application/x-protobuf")
+        .body(item.encode_to_vec())
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/x-protobuf"
+    );
+    let bytes = resp.bytes().await.map_err(Error::boxed)?;
+    assert_eq!(
+        Item::decode(bytes).unwrap(),
+        Item {
+            id: 2,
+            name: "viz".to_string()
+        }
+    );
+
+    let resp = client
+        .post("/items")
+        .header("content-type", "application/protobuf")
+        .body(vec![0xff])
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .post("/items")
+        .header("content-type", "application/json")
+        .body("{}")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 415);
+
+    let resp = client
+        .post("/items")
+        .header("content-type", "application/x-protobuf")
+        .body(
+            Item {
+                id: 1,
+                name: "v".repeat(64),
+            }
+            .encode_to_vec(),
+        )
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 413);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_json_lines() -> Result<()> {
+    use futures_util::{stream, StreamExt};
+    use viz::{
+        middleware::limits,
+        types::{JsonLines, Limits},
+        Router,
+    };
+    use viz_test::TestServer;
+
+    let router = Router::new()
+        .post("/pages", |mut req: Request| async move {
+            let mut lines = req.extract::<JsonLines<Page>>().await?;
+            let mut pages = Vec::new();
+            while let Some(page) = lines.next().await {
+                pages.push(Page { p: page?.p * 2 });
+            }
+            Ok(JsonLines::new(stream::iter(pages)))
+        })
+        .with(limits::Config::new().limits(Limits::new().set("json-lines", 16).sort()));
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client
+        .post("/pages")
+        .header("content-type", "application/x-ndjson")
+        .body("{\"p\":1}\n\n{\"p\":2}\r\n  {\"p\":3}")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/x-ndjson"
+    );
+    assert_eq!(
+        resp.text().await.map_err(Error::boxed)?,
+        "{\"p\":2}\n{\"p\":4}\n{\"p\":6}\n"
+    );
+
+    let resp = client
+        .post("/pages")
+        .header("content-type", "application/x-ndjson")
+        .body("{\"p\":1}\n{\"p\":1,\"padding\":\"..........\"}\n")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 413);
+
+    let resp = client
+        .post("/pages")
+        .header("content-type", "application/x-ndjson")
+        .body("{\"p\":1}\n{\"p\":}\n")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .post("/pages")
+        .header("content-type", "application/json")
+        .body("{\"p\":1}")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 415);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_nested_query() -> Result<()> {
+    use viz::{
+        types::{ArrayFormat, NestedQuery, QueryConfig, State},
+        HandlerExt, Router,
+    };
+    use viz_test::TestServer;
+
+    #[derive(Debug, Deserialize)]
+    struct Filter {
+        name: Option<String>,
+        age: Option<u8>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Search {
+        #[serde(default)]
+        tags: Vec<String>,
+        filter: Option<Filter>,
+    }
+
+    async fn search(mut req: Request) -> Result<String> {
+        let NestedQuery(search) = req.extract::<NestedQuery<Search>>().await?;
+        let filter = search.filter.unwrap_or(Filter {
+            name: None,
+            age: None,
+        });
+        Ok(format!(
+            "{}|{}|{}",
+            search.tags.join(","),
+            filter.name.unwrap_or_default(),
+            filter.age.unwrap_or_default()
+        ))
+    }
+
+    let router = Router::new().get("/search", search).get(
+        "/comma",
+        search.with(State::new(
+            QueryConfig::new().array_format(ArrayFormat::Comma),
+        )),
+    );
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client
+        .get("/search?tags=a&tags=b&filter[name]=viz&filter[age]=8")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "a,b|viz|8");
+
+    let resp = client
+        .get("/search?tags%5B%5D=a&tags%5B%5D=b")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "a,b||0");
+
+    let resp = client
+        .get("/comma?tags=a,b&tags=c")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "a,b,c||0");
+
+    let resp = client
+        .get("/search?filter[age]=old")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(
+        resp.text().await.map_err(Error::boxed)?,
+        "failed to deserialize query, `filter[age]`: invalid value `old`, invalid digit found in string"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_multipart_form() -> Result<()> {
+    use viz::{
+        types::{FileRule, MultipartForm, MultipartFormConfig, State},
+        HandlerExt, Router,
+    };
+    use viz_test::{
+        multipart::{Form, Part},
+        TestServer,
+    };
+
+    #[derive(Debug, Deserialize)]
+    struct Upload {
+        title: String,
+        #[serde(default)]
+        tags: Vec<String>,
+        // a text field is not taken as a file
+        photos: Option<String>,
+    }
+
+    async fn upload(mut req: Request) -> Result<String> {
+        let MultipartForm(upload, mut files) = req.extract::<MultipartForm<Upload>>().await?;
+        assert!(upload.photos.is_none());
+        let avatar = files.take("avatar");
+        let mut sizes = Vec::new();
+        for photo in files.take_all("photos") {
+            let bytes = photo.bytes().await?;
+            assert_eq!(bytes.len() as u64, photo.size());
+            sizes.push(format!(
+                "{}:{}:{}",
+                photo.file_name().unwrap_or_default(),
+                photo.size(),
+                photo.path().is_some()
+            ));
+
+            let temp = photo.path().map(std::path::Path::to_path_buf);
+            let path = std::env::temp_dir().join(format!(
+                "viz-{}-{}",
+                std::process::id(),
+                photo.file_name().unwrap_or_default()
+            ));
+            let path = photo.persist(path).await?;
+            assert_eq!(std::fs::read(&path)?, bytes);
+            assert!(temp.map_or(true, |temp| !temp.exists()));
+            std::fs::remove_file(path)?;
+        }
+        Ok(format!(
+            "{}|{}|{}|{}",
+            upload.title,
+            upload.tags.join(","),
+            sizes.join(","),
+            avatar.is_some()
+        ))
+    }
+
+    let router = Router::new().post(
+        "/upload",
+        upload.with(State::new(
+            MultipartFormConfig::new()
+                .memory_threshold(8)
+                .max_file_size(32)
+                .allow(mime::IMAGE_STAR)
+                .field("avatar", FileRule::new().max_size(4).allow(mime::IMAGE_PNG)),
+        )),
+    );
+
+    let client = TestServer::new(router).await?;
+
+    let photo = |name: &'static str, size: usize| {
+        Part::bytes(vec![b'x'; size])
+            .file_name(name)
+            .mime_str("image/jpeg")
+            .unwrap()
+    };
+
+    let form = Form::new()
+        .text("title", "viz")
+        .text("tags", "a")
+        .text("tags", "b")
+        .part("photos", photo("a.jpg", 4))
+        .part("photos", photo("../../b.jpg", 16))
+        .part("avatar", Part::bytes(Vec::new()).file_name(""));
+    let resp = client
+        .post("/upload")
+        .multipart(form)
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.text().await.map_err(Error::boxed)?,
+        "viz|a,b|a.jpg:4:false,b.jpg:16:true|false"
+    );
+
+    let form = Form::new()
+        .text("title", "viz")
+        .part("photos", photo("a.jpg", 64));
+    let resp = client
+        .post("/upload")
+        .multipart(form)
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 413);
+
+    let form = Form::new().text("title", "viz").part(
+        "avatar",
+        Part::bytes(vec![b'x'; 2])
+            .file_name("a.jpg")
+            .mime_str("image/jpeg")
+            .unwrap(),
+    );
+    let resp = client
+        .post("/upload")
+        .multipart(form)
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 415);
+
+    let form = Form::new().part("photos", photo("a.jpg", 4));
+    let resp = client
+        .post("/upload")
+        .multipart(form)
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 400);
+
+    Ok(())
+}
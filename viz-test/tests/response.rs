@@ -173,3 +173,77 @@ async fn response_ext_with_server() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn response_negotiate() -> Result<()> {
+    use viz::{
+        types::{Accept, Negotiate},
+        Request, RequestExt, Router,
+    };
+    use viz_test::TestServer;
+
+    let router = Router::new()
+        .get("/page", |mut req: Request| async move {
+            let accept = req.extract::<Accept>().await?;
+            Ok(Negotiate::new(accept, Page { p: 1 }))
+        })
+        .get("/name", |mut req: Request| async move {
+            let accept = req.extract::<Accept>().await?;
+            Ok(Negotiate::new(accept, "viz"))
+        });
+
+    let client = TestServer::new(router).await?;
+
+    let resp = client.get("/page").send().await.map_err(Error::boxed)?;
+    assert_eq!(
+        resp.headers().get(http::header::CONTENT_TYPE).unwrap(),
+        "application/json"
+    );
+    assert_eq!(resp.headers().get(http::header::VARY).unwrap(), "accept");
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, r#"{"p":1}"#);
+
+    let resp = client
+        .get("/page")
+        .header(
+            http::header::ACCEPT,
+            "text/html;q=0.9, application/x-www-form-urlencoded",
+        )
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(
+        resp.headers().get(http::header::CONTENT_TYPE).unwrap(),
+        "application/x-www-form-urlencoded"
+    );
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "p=1");
+
+    let resp = client
+        .get("/page")
+        .header(http::header::ACCEPT, "application/vnd.api+json")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, r#"{"p":1}"#);
+
+    let resp = client
+        .get("/name")
+        .header(http::header::ACCEPT, "application/json;q=0, text/*")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(
+        resp.headers().get(http::header::CONTENT_TYPE).unwrap(),
+        "text/plain; charset=utf-8"
+    );
+    assert_eq!(resp.text().await.map_err(Error::boxed)?, "viz");
+
+    let resp = client
+        .get("/page")
+        .header(http::header::ACCEPT, "image/png")
+        .send()
+        .await
+        .map_err(Error::boxed)?;
+    assert_eq!(resp.status(), 406);
+
+    Ok(())
+}
@@ -98,6 +98,10 @@
 //!
 //! Extractors must implement the [`FromRequest`] trait for extracting data from the [`Request`].
 //!
+//! Only the last extractor can consume the body, e.g. `Json` or `Form`, the others must implement
+//! the [`FromRequestParts`] trait, which extracts data from the headers, the params, the state or
+//! the cookies. The parts extractors are extracted concurrently.
+//!
 //! When joining the routing system, it should first be converted to a handler using
 //! [`into_handler`][IntoHandler::into_handler].
 //!
@@ -110,6 +114,19 @@
 //! let app = Router::new().route("/:id", get(show_todo.into_handler()));
 //! ```
 //!
+//! The last argument can be the remaining [`Request`] after the extractors, e.g. for streaming
+//! the body. And the handler can return any [`IntoResponse`] type, not only the [`Result`].
+//!
+//! ```
+//! # use viz::{get, types::Params, IntoHandler, Request, RequestExt, Router};
+//! async fn upload(Params(id): Params<u64>, mut req: Request) -> String {
+//!     let size = req.bytes().await.map(|bytes| bytes.len()).unwrap_or_default();
+//!     format!("NO.{id} uploaded {size} bytes")
+//! }
+//!
+//! let app = Router::new().route("/:id", get(upload.into_handler()));
+//! ```
+//!
 //! ### Why not supports handler with extractors by default?
 //!
 //! Viz allows more flexibility in organizing your code.
@@ -142,7 +159,9 @@
 //!
 //! ### Support process macros?
 //!
-//! Support, you can enable the `macros` feature, using `#[handler]`.
+//! Support, you can enable the `macros` feature, using `#[handler]`, or the route macros,
+//! e.g. `#[get("/users/:id")]`, which can be mounted by the [`Router::mount`] with the
+//! [`routes!`] macro.
 //!
 //! But it's still recommended to use `into_handler` for conversion.
 //!
@@ -340,19 +359,29 @@
 //!
 //! Extracts data from the [`Request`].
 //!
+//! An extractor which does not consume the body should implement the [`FromRequestParts`] too,
+//! then it can be used before the other extractors.
+//!
 //! ```
 //! # use std::{cmp, convert::Infallible};
-//! # use viz::{FromRequest, Request, RequestExt, Result};
+//! # use viz::{FromRequest, FromRequestParts, Request, RequestExt, Result};
 //! struct Counter(u16);
 //!
-//! impl FromRequest for Counter {
+//! impl FromRequestParts for Counter {
 //!     type Error = Infallible;
-//!     async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+//!     async fn extract_parts(req: &Request) -> Result<Self, Self::Error> {
 //!         let c = get_query_param(req.query_string());
 //!         Ok(Counter(c))
 //!     }
 //! }
 //!
+//! impl FromRequest for Counter {
+//!     type Error = Infallible;
+//!     async fn extract(req: &mut Request) -> Result<Self, Self::Error> {
+//!         Self::extract_parts(req).await
+//!     }
+//! }
+//!
 //! fn get_query_param(query: Option<&str>) -> u16 {
 //!    let query = query.unwrap_or("");
 //!    let q = if let Some(pos) = query.find('q') {
@@ -510,6 +539,81 @@
 //!   .route("/*", any(not_found));
 //! ```
 //!
+//! ## Application state
+//!
+//! The routes added by [`Router::stateful`] or the verbs, e.g. [`Router::stateful_get`], take
+//! the [`State`][types::State] of a sub-state as the first argument, the sub-state is got from
+//! the application state by the [`FromRef`][types::FromRef]. The router can only be served after
+//! the application state is provided by [`Router::with_state`], so a missing state is a compile
+//! error.
+//!
+//! ```
+//! # use viz::{types::{FromRef, Params, State}, Result, Router};
+//! #[derive(Clone)]
+//! struct AppState {
+//!     db: Db,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct Db;
+//!
+//! impl FromRef<AppState> for Db {
+//!     fn from_ref(state: &AppState) -> Self {
+//!         state.db.clone()
+//!     }
+//! }
+//!
+//! async fn show_user(State(db): State<Db>, Params(id): Params<u64>) -> Result<String> {
+//!     Ok(format!("user {id}"))
+//! }
+//!
+//! let app: Router = Router::default()
+//!   .stateful_get("/users/:id", show_user)
+//!   .with_state(AppState { db: Db });
+//! ```
+//!
+//! The router can not be cloned before the state is provided, so the state is bound once.
+//!
+//! ```compile_fail
+//! # use viz::{types::State, Router};
+//! async fn count(State(n): State<u64>) -> String {
+//!     n.to_string()
+//! }
+//!
+//! let router = Router::<u64>::default().stateful_get("/count", count);
+//! let cloned = router.clone();
+//! ```
+//!
+//! ## Dependencies
+//!
+//! The factories registered by [`Router::provide`] are resolved lazily by the
+//! [`Inject`][types::Inject] extractor, and cached in the request. The finalizer registered by
+//! [`Router::provide_with`] sees the result of the handler.
+//!
+//! ```
+//! # use viz::{types::Inject, IntoHandler, Response, Result, Router};
+//! #[derive(Clone)]
+//! struct Db;
+//!
+//! #[derive(Clone)]
+//! struct Tx(Db);
+//!
+//! async fn create_user(Inject(tx): Inject<Tx>) -> Result<&'static str> {
+//!     Ok("created")
+//! }
+//!
+//! let app = Router::new()
+//!   .post("/users", create_user.into_handler())
+//!   .provide(|| async { Ok(Db) })
+//!   .provide_with(
+//!     |Inject(db): Inject<Db>| async move { Ok(Tx(db)) },
+//!     |tx: Tx, result: &Result<Response>| {
+//!       let commit = result.is_ok();
+//!       async move { Ok(()) }
+//!     },
+//!   );
+//! ```
+//!
 //! [`FutureExt`]: https://docs.rs/futures/latest/futures/future/trait.FutureExt.html
 //! [`StreamExt`]: https://docs.rs/futures/latest/futures/stream/trait.StreamExt.html
 //! [`Service`]: https://docs.rs/tower-service/latest/tower_service/trait.Service.html
@@ -545,4 +649,7 @@ pub use viz_handlers as handlers;
 #[cfg(feature = "macros")]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 #[doc(inline)]
-pub use viz_macros::handler;
+pub use viz_macros::{
+    connect, delete, get, handler, head, options, patch, post, put, trace, FromRequest,
+    IntoResponse, TypedHeader, TypedPath,
+};